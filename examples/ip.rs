@@ -22,7 +22,7 @@ use smoltcp::socket::{SocketSet, TcpSocket, TcpSocketBuffer};
 use smoltcp::time::Instant;
 use smoltcp::wire::{EthernetAddress, IpAddress, IpCidr, Ipv4Address};
 
-use stm32_eth::{Eth, RingEntry};
+use stm32_eth::{Eth, MediaInterface, RingEntry};
 
 static mut LOGGER: HioLogger = HioLogger {};
 
@@ -62,7 +62,7 @@ fn main() -> ! {
     setup_systick(&mut cp.SYST);
 
     writeln!(stdout, "Enabling ethernet...").unwrap();
-    stm32_eth::setup(&p.RCC, &p.SYSCFG);
+    stm32_eth::setup(&p.RCC, &p.SYSCFG, MediaInterface::Rmii);
     let gpioa = p.GPIOA.split();
     let gpiob = p.GPIOB.split();
     let gpioc = p.GPIOC.split();
@@ -79,8 +79,9 @@ fn main() -> ! {
         p.ETHERNET_DMA,
         &mut rx_ring[..],
         &mut tx_ring[..],
-    );
-    eth.enable_interrupt(&mut cp.NVIC);
+    )
+    .unwrap();
+    eth.enable_interrupt();
 
     let local_addr = Ipv4Address::new(10, 0, 0, 1);
     let ip_addr = IpCidr::new(IpAddress::from(local_addr), 24);
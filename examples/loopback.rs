@@ -0,0 +1,121 @@
+//! On-target test of the whole descriptor path, without a link
+//! partner
+//!
+//! Frames are sent with the MAC in loopback mode, and must be
+//! received unchanged. Run with
+//! `--features stm32f4xx,nucleo-f429zi,<phy>,test-hardware`.
+
+#![no_std]
+#![no_main]
+
+extern crate panic_itm;
+
+use core::default::Default;
+use cortex_m_rt::entry;
+
+use stm32f4xx_hal::{gpio::GpioExt, stm32::Peripherals};
+
+use core::fmt::Write;
+use cortex_m_semihosting::{debug, hio};
+
+use stm32_eth::{Config, Eth, MediaInterface, RingEntry, RxError, TxError};
+
+const MAC: [u8; 6] = [0x02, 0x00, 0xDE, 0xAD, 0xBE, 0xEF];
+/// Local experimental EtherType, so that no checksums are inserted
+const ETH_TYPE: [u8; 2] = [0x88, 0xB5];
+const SIZE: usize = 100;
+const ROUNDS: usize = 1000;
+/// How many times to poll for a frame before giving up
+const RECV_TIMEOUT_POLLS: usize = 100_000;
+
+#[entry]
+fn main() -> ! {
+    let mut stdout = hio::hstdout().unwrap();
+
+    let p = Peripherals::take().unwrap();
+
+    stm32_eth::setup(&p.RCC, &p.SYSCFG, MediaInterface::Rmii);
+    let gpioa = p.GPIOA.split();
+    let gpiob = p.GPIOB.split();
+    let gpioc = p.GPIOC.split();
+    let gpiog = p.GPIOG.split();
+    stm32_eth::setup_pins(
+        gpioa.pa1, gpioa.pa2, gpioa.pa7, gpiob.pb13, gpioc.pc1, gpioc.pc4, gpioc.pc5, gpiog.pg11,
+        gpiog.pg13,
+    );
+
+    let mut rx_ring: [RingEntry<_>; 4] = Default::default();
+    let mut tx_ring: [RingEntry<_>; 4] = Default::default();
+    // The link state does not matter in loopback mode
+    let config = Config {
+        fixed_link: true,
+        ..Config::default()
+    };
+    let mut eth = Eth::new_with_config(
+        p.ETHERNET_MAC,
+        p.ETHERNET_DMA,
+        &mut rx_ring[..],
+        &mut tx_ring[..],
+        config,
+    )
+    .unwrap();
+    eth.set_mac_address(&MAC);
+    eth.set_loopback(true);
+
+    for round in 0..ROUNDS {
+        let seed = round as u8;
+        match eth.send(SIZE, |buf| fill(buf, seed)) {
+            Ok(()) => {}
+            Err(TxError::WouldBlock) => fail(&mut stdout, round, "Tx ring full"),
+            Err(_) => fail(&mut stdout, round, "send failed"),
+        }
+
+        let mut expected = [0; SIZE];
+        fill(&mut expected, seed);
+
+        let mut received = false;
+        for _ in 0..RECV_TIMEOUT_POLLS {
+            match eth.recv_next() {
+                Ok(pkt) => {
+                    if pkt.len() < SIZE || pkt[..SIZE] != expected[..] {
+                        fail(&mut stdout, round, "received frame differs");
+                    }
+                    received = true;
+                    break;
+                }
+                Err(RxError::WouldBlock) => {}
+                Err(_) => fail(&mut stdout, round, "receive failed"),
+            }
+        }
+        if !received {
+            fail(&mut stdout, round, "no frame received");
+        }
+        loop {
+            match eth.poll_tx_complete() {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(_) => fail(&mut stdout, round, "transmission failed"),
+            }
+        }
+    }
+
+    writeln!(stdout, "loopback: {} frames OK", ROUNDS).unwrap();
+    debug::exit(debug::EXIT_SUCCESS);
+    loop {}
+}
+
+/// Frame to ourselves, with a payload that differs per round
+fn fill(buf: &mut [u8], seed: u8) {
+    buf[0..6].copy_from_slice(&MAC);
+    buf[6..12].copy_from_slice(&MAC);
+    buf[12..14].copy_from_slice(&ETH_TYPE);
+    for (i, b) in buf[14..].iter_mut().enumerate() {
+        *b = seed.wrapping_add(i as u8);
+    }
+}
+
+fn fail(stdout: &mut hio::HStdout, round: usize, reason: &str) -> ! {
+    writeln!(stdout, "loopback: round {}: {}", round, reason).unwrap();
+    debug::exit(debug::EXIT_FAILURE);
+    loop {}
+}
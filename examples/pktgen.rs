@@ -17,7 +17,7 @@ use stm32f4xx_hal::{
 use core::fmt::Write;
 use cortex_m_semihosting::hio;
 
-use stm32_eth::{Eth, RingEntry, TxError};
+use stm32_eth::{phy::Speed, Eth, MediaInterface, RingEntry, TxError};
 
 const SRC_MAC: [u8; 6] = [0x00, 0x00, 0xDE, 0xAD, 0xBE, 0xEF];
 const DST_MAC: [u8; 6] = [0x00, 0x00, 0xBE, 0xEF, 0xDE, 0xAD];
@@ -36,7 +36,7 @@ fn main() -> ! {
     setup_systick(&mut cp.SYST);
 
     writeln!(stdout, "Enabling ethernet...").unwrap();
-    stm32_eth::setup(&p.RCC, &p.SYSCFG);
+    stm32_eth::setup(&p.RCC, &p.SYSCFG, MediaInterface::Rmii);
     let gpioa = p.GPIOA.split();
     let gpiob = p.GPIOB.split();
     let gpioc = p.GPIOC.split();
@@ -53,8 +53,9 @@ fn main() -> ! {
         p.ETHERNET_DMA,
         &mut rx_ring[..],
         &mut tx_ring[..],
-    );
-    eth.enable_interrupt(&mut cp.NVIC);
+    )
+    .unwrap();
+    eth.enable_interrupt();
 
     // Main loop
     let mut last_stats_time = 0usize;
@@ -100,7 +101,11 @@ fn main() -> ! {
                 writeln!(
                     stdout,
                     "Ethernet: link detected with {} Mbps/{}",
-                    status.speed(),
+                    match status.speed() {
+                        Some(Speed::TenMbps) => "10",
+                        Some(Speed::HundredMbps) => "100",
+                        None => "?",
+                    },
                     match status.is_full_duplex() {
                         Some(true) => "FD",
                         Some(false) => "HD",
@@ -153,6 +158,7 @@ fn main() -> ! {
                         tx_pkts += 1;
                     }
                     Err(TxError::WouldBlock) => break 'egress,
+                    Err(e) => panic!("send: {:?}", e),
                 }
             }
         }
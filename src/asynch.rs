@@ -0,0 +1,93 @@
+//! `async` API, woken by the Ethernet interrupt
+//!
+//! Requires [`Eth::enable_interrupt()`](../struct.Eth.html#method.enable_interrupt)
+//! and an interrupt handler that calls
+//! [`eth_interrupt_handler()`](../fn.eth_interrupt_handler.html).
+
+use core::future::poll_fn;
+use core::task::{Context, Poll};
+
+use atomic_waker::AtomicWaker;
+
+use crate::{rx::RxPacket, Eth, InterruptReason, RxError, TxError};
+
+static RX_WAKER: AtomicWaker = AtomicWaker::new();
+static TX_WAKER: AtomicWaker = AtomicWaker::new();
+
+/// Wake the tasks that wait for whatever caused the interrupt
+pub(crate) fn on_interrupt(reason: &InterruptReason) {
+    if reason.rx {
+        RX_WAKER.wake();
+    }
+    if reason.tx {
+        TX_WAKER.wake();
+    }
+}
+
+impl<'rx, 'tx> Eth<'rx, 'tx> {
+    /// Wait until a packet has been received
+    pub async fn recv_async(&mut self) -> Result<RxPacket<'_>, RxError> {
+        poll_fn(|cx| {
+            if self.rx_ring.next_entry_ready() {
+                return Poll::Ready(());
+            }
+            RX_WAKER.register(cx.waker());
+            // Check again in case the interrupt fired before the
+            // waker was registered
+            if self.rx_ring.next_entry_ready() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+
+        self.recv_next()
+    }
+
+    /// Wait until a Tx descriptor is free, then send a packet
+    pub async fn send_async<F: FnOnce(&mut [u8]) -> R, R>(
+        &mut self,
+        length: usize,
+        f: F,
+    ) -> Result<R, TxError> {
+        poll_fn(|cx| {
+            if self.poll_tx_available(cx) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+
+        self.send(length, f)
+    }
+
+    /// Send a packet if a Tx descriptor is free
+    ///
+    /// Otherwise, returns `Poll::Pending` without calling `f`, and
+    /// wakes the task once a packet has been transmitted.
+    pub fn poll_send<F: FnOnce(&mut [u8]) -> R, R>(
+        &mut self,
+        cx: &mut Context<'_>,
+        length: usize,
+        f: F,
+    ) -> Poll<Result<R, TxError>> {
+        if self.poll_tx_available(cx) {
+            Poll::Ready(self.send(length, f))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Is the next Tx descriptor free? If not, register the waker.
+    fn poll_tx_available(&self, cx: &mut Context<'_>) -> bool {
+        if self.tx_ring.next_entry_available() {
+            return true;
+        }
+        TX_WAKER.register(cx.waker());
+        // Check again in case the interrupt fired before the waker
+        // was registered
+        self.tx_ring.next_entry_available()
+    }
+}
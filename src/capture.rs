@@ -0,0 +1,138 @@
+//! Retention of the last received frames for debugging
+
+use core::ops::Deref;
+
+use crate::Eth;
+
+/// Most frames that [`Eth::enable_capture()`](struct.Eth.html#method.enable_capture)
+/// can retain
+pub const CAPTURE_MAX_FRAMES: usize = 16;
+/// Most bytes per frame that are retained
+pub const CAPTURE_MAX_BYTES: usize = 128;
+
+/// The beginning of a received frame, returned by
+/// [`Eth::drain_capture()`](struct.Eth.html#method.drain_capture)
+///
+/// Dereferences to the captured bytes.
+#[derive(Clone)]
+pub struct CapturedFrame {
+    data: [u8; CAPTURE_MAX_BYTES],
+    captured: usize,
+    length: usize,
+}
+
+impl CapturedFrame {
+    const fn new() -> Self {
+        CapturedFrame {
+            data: [0; CAPTURE_MAX_BYTES],
+            captured: 0,
+            length: 0,
+        }
+    }
+
+    /// Length of the whole frame as received
+    pub fn frame_len(&self) -> usize {
+        self.length
+    }
+
+    /// Was the frame longer than what has been captured?
+    pub fn is_truncated(&self) -> bool {
+        self.length > self.captured
+    }
+}
+
+impl Deref for CapturedFrame {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.data[..self.captured]
+    }
+}
+
+/// Ring of the most recently received frames, oldest first
+pub(crate) struct CaptureRing {
+    frames: [CapturedFrame; CAPTURE_MAX_FRAMES],
+    /// Number of frames to retain, 0 while disabled
+    capacity: usize,
+    /// Bytes to retain of each frame
+    bytes: usize,
+    /// Index of the oldest frame
+    first: usize,
+    count: usize,
+}
+
+impl CaptureRing {
+    pub(crate) const fn new() -> Self {
+        const EMPTY: CapturedFrame = CapturedFrame::new();
+        CaptureRing {
+            frames: [EMPTY; CAPTURE_MAX_FRAMES],
+            capacity: 0,
+            bytes: 0,
+            first: 0,
+            count: 0,
+        }
+    }
+
+    /// Copy the beginning of `frame`, replacing the oldest frame
+    /// once the ring is full
+    pub(crate) fn record(&mut self, frame: &[u8]) {
+        if self.capacity == 0 {
+            return;
+        }
+        let index = (self.first + self.count) % self.capacity;
+        if self.count == self.capacity {
+            self.first = (self.first + 1) % self.capacity;
+        } else {
+            self.count += 1;
+        }
+
+        let captured = frame.len().min(self.bytes);
+        let slot = &mut self.frames[index];
+        slot.data[..captured].copy_from_slice(&frame[..captured]);
+        slot.captured = captured;
+        slot.length = frame.len();
+    }
+}
+
+impl<'rx, 'tx> Eth<'rx, 'tx> {
+    /// Retain the first `bytes` bytes of the last `frames` received
+    /// frames, to be dumped with
+    /// [`drain_capture()`](#method.drain_capture) after a failure
+    ///
+    /// Every frame returned by [`recv_next()`](#method.recv_next)
+    /// is copied, which costs time on each receive. This includes
+    /// the frames received through
+    /// [`recv_with()`](#method.recv_with),
+    /// [`for_each_frame()`](#method.for_each_frame),
+    /// [`rx_frames()`](#method.rx_frames), and the smoltcp and async
+    /// integrations, which all go through `recv_next()`.
+    /// Frames that have been captured before are discarded. Passing
+    /// 0 for `frames` disables capturing.
+    ///
+    /// # Panics
+    ///
+    /// If `frames` exceeds [`CAPTURE_MAX_FRAMES`](constant.CAPTURE_MAX_FRAMES.html)
+    /// or `bytes` exceeds [`CAPTURE_MAX_BYTES`](constant.CAPTURE_MAX_BYTES.html).
+    pub fn enable_capture(&mut self, frames: usize, bytes: usize) {
+        assert!(frames <= CAPTURE_MAX_FRAMES, "Too many frames to capture");
+        assert!(bytes <= CAPTURE_MAX_BYTES, "Too many bytes to capture");
+        let capture = &mut self.capture;
+        capture.capacity = frames;
+        capture.bytes = bytes;
+        capture.first = 0;
+        capture.count = 0;
+    }
+
+    /// Take the captured frames, oldest first
+    ///
+    /// The ring is empty afterwards, and capturing continues.
+    pub fn drain_capture(&mut self) -> impl Iterator<Item = &CapturedFrame> + '_ {
+        let capture = &mut self.capture;
+        let (first, count, capacity) = (capture.first, capture.count, capture.capacity);
+        capture.first = 0;
+        capture.count = 0;
+
+        let frames = &capture.frames;
+        (0..count).map(move |i| &frames[(first + i) % capacity])
+    }
+}
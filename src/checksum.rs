@@ -0,0 +1,266 @@
+//! Software verification of IP checksums, for frames that the MAC
+//! did not check
+
+use crate::rx::ChecksumResult;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86DD;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+/// Offset of the EtherType in an untagged frame
+const ETHERTYPE_OFFSET: usize = 12;
+const VLAN_TAG_LEN: usize = 4;
+
+const PROTOCOL_ICMP: u8 = 1;
+const PROTOCOL_TCP: u8 = 6;
+const PROTOCOL_UDP: u8 = 17;
+const PROTOCOL_ICMPV6: u8 = 58;
+
+const IPV6_HEADER_LEN: usize = 40;
+/// Flags and fragment offset of an IPv4 header, without the don't
+/// fragment flag
+const IPV4_FRAGMENT_MASK: u16 = 0x3FFF;
+
+/// Verify the IP header and payload checksums of `frame`
+///
+/// Frames that are not IP, fragmented, or of another protocol than
+/// TCP, UDP, and ICMP are `NotChecked`, as are UDP datagrams without
+/// checksum.
+pub(crate) fn verify(frame: &[u8]) -> (ChecksumResult, ChecksumResult) {
+    let not_checked = (ChecksumResult::NotChecked, ChecksumResult::NotChecked);
+    let mut offset = ETHERTYPE_OFFSET;
+    let mut ethertype = match read_u16(frame, offset) {
+        Some(ethertype) => ethertype,
+        None => return not_checked,
+    };
+    if ethertype == ETHERTYPE_VLAN {
+        offset += VLAN_TAG_LEN;
+        ethertype = match read_u16(frame, offset) {
+            Some(ethertype) => ethertype,
+            None => return not_checked,
+        };
+    }
+    let packet = &frame[offset + 2..];
+
+    match ethertype {
+        ETHERTYPE_IPV4 => verify_ipv4(packet).unwrap_or(not_checked),
+        ETHERTYPE_IPV6 => verify_ipv6(packet).unwrap_or(not_checked),
+        _ => not_checked,
+    }
+}
+
+fn verify_ipv4(packet: &[u8]) -> Option<(ChecksumResult, ChecksumResult)> {
+    let header_len = usize::from(packet.first()? & 0xF) * 4;
+    let total_len = usize::from(read_u16(packet, 2)?);
+    if header_len < 20 || total_len < header_len || total_len > packet.len() {
+        return None;
+    }
+    let header = checksum_result(sum(&packet[..header_len], 0));
+
+    if read_u16(packet, 6)? & IPV4_FRAGMENT_MASK != 0 {
+        return Some((header, ChecksumResult::NotChecked));
+    }
+    let protocol = packet[9];
+    let payload = &packet[header_len..total_len];
+    let pseudo_header = || {
+        let addresses = sum(&packet[12..20], 0);
+        addresses + u32::from(protocol) + payload.len() as u32
+    };
+    let payload = match protocol {
+        PROTOCOL_ICMP => checksum_result(sum(payload, 0)),
+        PROTOCOL_TCP => checksum_result(sum(payload, pseudo_header())),
+        PROTOCOL_UDP if read_u16(payload, 6) != Some(0) => {
+            checksum_result(sum(payload, pseudo_header()))
+        }
+        _ => ChecksumResult::NotChecked,
+    };
+    Some((header, payload))
+}
+
+fn verify_ipv6(packet: &[u8]) -> Option<(ChecksumResult, ChecksumResult)> {
+    let payload_len = usize::from(read_u16(packet, 4)?);
+    let next_header = *packet.get(6)?;
+    let payload = packet.get(IPV6_HEADER_LEN..IPV6_HEADER_LEN + payload_len)?;
+
+    let payload = match next_header {
+        PROTOCOL_TCP | PROTOCOL_UDP | PROTOCOL_ICMPV6 => {
+            let pseudo_header =
+                sum(&packet[8..IPV6_HEADER_LEN], 0) + u32::from(next_header) + payload_len as u32;
+            checksum_result(sum(payload, pseudo_header))
+        }
+        // Extension headers are not followed
+        _ => ChecksumResult::NotChecked,
+    };
+    // IPv6 has no header checksum
+    Some((ChecksumResult::NotChecked, payload))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    match data.get(offset..offset + 2)? {
+        &[high, low] => Some(u16::from_be_bytes([high, low])),
+        _ => None,
+    }
+}
+
+/// Add `data` as big-endian 16-bit words to `initial`, without
+/// folding the carries
+fn sum(data: &[u8], initial: u32) -> u32 {
+    let mut words = data.chunks_exact(2);
+    let mut sum = words.by_ref().fold(initial, |sum, word| {
+        sum + u32::from(u16::from_be_bytes([word[0], word[1]]))
+    });
+    if let [last] = words.remainder() {
+        sum += u32::from(*last) << 8;
+    }
+    sum
+}
+
+/// The ones' complement sum over data including its checksum is all
+/// ones if the checksum is correct
+fn checksum_result(mut sum: u32) -> ChecksumResult {
+    while sum >> 16 != 0 {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    if sum == 0xFFFF {
+        ChecksumResult::Valid
+    } else {
+        ChecksumResult::Invalid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::verify;
+    use crate::rx::ChecksumResult;
+    use crate::rx::ChecksumResult::{Invalid, NotChecked, Valid};
+
+    /// Offset of the IPv4 time to live, covered only by the header
+    /// checksum
+    const IPV4_TTL: usize = 22;
+
+    /// 192.168.1.2:1234 to 192.168.1.1:5678, "ping"
+    const IPV4_UDP: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x02, 0x08, 0x00, 0x45,
+        0x00, 0x00, 0x20, 0x12, 0x34, 0x40, 0x00, 0x40, 0x11, 0xA5, 0x45, 0xC0, 0xA8, 0x01, 0x02,
+        0xC0, 0xA8, 0x01, 0x01, 0x04, 0xD2, 0x16, 0x2E, 0x00, 0x0C, 0x82, 0xB1, 0x70, 0x69, 0x6E,
+        0x67,
+    ];
+
+    /// As `IPV4_UDP`, without UDP checksum
+    const IPV4_UDP_NO_CHECKSUM: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x02, 0x08, 0x00, 0x45,
+        0x00, 0x00, 0x20, 0x12, 0x34, 0x40, 0x00, 0x40, 0x11, 0xA5, 0x45, 0xC0, 0xA8, 0x01, 0x02,
+        0xC0, 0xA8, 0x01, 0x01, 0x04, 0xD2, 0x16, 0x2E, 0x00, 0x0C, 0x00, 0x00, 0x70, 0x69, 0x6E,
+        0x67,
+    ];
+
+    /// 192.168.1.2:1234 to 192.168.1.1:80, PSH ACK "ping"
+    const IPV4_TCP: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x02, 0x08, 0x00, 0x45,
+        0x00, 0x00, 0x2C, 0x12, 0x34, 0x40, 0x00, 0x40, 0x06, 0xA5, 0x44, 0xC0, 0xA8, 0x01, 0x02,
+        0xC0, 0xA8, 0x01, 0x01, 0x04, 0xD2, 0x00, 0x50, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+        0x00, 0x50, 0x18, 0x04, 0x00, 0x44, 0x81, 0x00, 0x00, 0x70, 0x69, 0x6E, 0x67,
+    ];
+
+    /// Echo request from 192.168.1.2 to 192.168.1.1, "ping"
+    const IPV4_ICMP: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x02, 0x08, 0x00, 0x45,
+        0x00, 0x00, 0x20, 0x12, 0x34, 0x40, 0x00, 0x40, 0x01, 0xA5, 0x55, 0xC0, 0xA8, 0x01, 0x02,
+        0xC0, 0xA8, 0x01, 0x01, 0x08, 0x00, 0x19, 0x2D, 0x00, 0x01, 0x00, 0x01, 0x70, 0x69, 0x6E,
+        0x67,
+    ];
+
+    /// [fe80::2]:1234 to [fe80::1]:5678, "ping"
+    const IPV6_UDP: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x02, 0x86, 0xDD, 0x60,
+        0x00, 0x00, 0x00, 0x00, 0x0C, 0x11, 0x40, 0xFE, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xFE, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x04, 0xD2, 0x16, 0x2E, 0x00, 0x0C,
+        0x09, 0x01, 0x70, 0x69, 0x6E, 0x67,
+    ];
+
+    /// [fe80::2]:1234 to [fe80::1]:80, PSH ACK "ping"
+    const IPV6_TCP: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x02, 0x86, 0xDD, 0x60,
+        0x00, 0x00, 0x00, 0x00, 0x18, 0x06, 0x40, 0xFE, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xFE, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x04, 0xD2, 0x00, 0x50, 0x00, 0x00,
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x50, 0x18, 0x04, 0x00, 0xCA, 0xD0, 0x00, 0x00, 0x70,
+        0x69, 0x6E, 0x67,
+    ];
+
+    /// Echo request from fe80::2 to fe80::1, "ping"
+    const IPV6_ICMPV6: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x02, 0x86, 0xDD, 0x60,
+        0x00, 0x00, 0x00, 0x00, 0x0C, 0x3A, 0x40, 0xFE, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xFE, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x80, 0x00, 0xA3, 0xE1, 0x00, 0x01,
+        0x00, 0x01, 0x70, 0x69, 0x6E, 0x67,
+    ];
+
+    /// Verify `frame` with the byte at `offset` flipped
+    fn verify_corrupted(frame: &[u8], offset: usize) -> (ChecksumResult, ChecksumResult) {
+        let mut buffer = [0; 128];
+        let buffer = &mut buffer[..frame.len()];
+        buffer.copy_from_slice(frame);
+        buffer[offset] ^= 0xFF;
+        verify(buffer)
+    }
+
+    #[test]
+    fn ipv4_header() {
+        assert_eq!(verify_corrupted(IPV4_UDP, IPV4_TTL), (Invalid, Valid));
+    }
+
+    #[test]
+    fn ipv4_payloads() {
+        for &frame in [IPV4_UDP, IPV4_TCP, IPV4_ICMP].iter() {
+            let last = frame.len() - 1;
+            assert_eq!(verify(frame), (Valid, Valid), "valid {:02X?}", frame);
+            assert_eq!(
+                verify_corrupted(frame, last),
+                (Valid, Invalid),
+                "corrupted {:02X?}",
+                frame
+            );
+        }
+    }
+
+    #[test]
+    fn ipv6_payloads() {
+        for &frame in [IPV6_UDP, IPV6_TCP, IPV6_ICMPV6].iter() {
+            let last = frame.len() - 1;
+            assert_eq!(verify(frame), (NotChecked, Valid), "valid {:02X?}", frame);
+            assert_eq!(
+                verify_corrupted(frame, last),
+                (NotChecked, Invalid),
+                "corrupted {:02X?}",
+                frame
+            );
+        }
+    }
+
+    #[test]
+    fn udp_without_checksum() {
+        let last = IPV4_UDP_NO_CHECKSUM.len() - 1;
+        assert_eq!(verify(IPV4_UDP_NO_CHECKSUM), (Valid, NotChecked));
+        assert_eq!(
+            verify_corrupted(IPV4_UDP_NO_CHECKSUM, last),
+            (Valid, NotChecked)
+        );
+    }
+
+    #[test]
+    fn truncated_packets() {
+        for &frame in [IPV4_UDP, IPV4_TCP, IPV6_UDP, IPV6_ICMPV6].iter() {
+            let truncated = &frame[..frame.len() - 1];
+            assert_eq!(
+                verify(truncated),
+                (NotChecked, NotChecked),
+                "truncated {:02X?}",
+                truncated
+            );
+        }
+        assert_eq!(verify(&IPV4_UDP[..13]), (NotChecked, NotChecked));
+        assert_eq!(verify(&IPV4_UDP[..20]), (NotChecked, NotChecked));
+    }
+}
@@ -0,0 +1,289 @@
+//! Configuration for [`Eth::new_with_config()`](../struct.Eth.html#method.new_with_config)
+
+use crate::phy::{Duplex, Speed};
+use crate::PassControlFrames;
+
+/// Settings that are applied when the Ethernet hardware is
+/// initialized
+///
+/// Start from `Config::default()`, which matches what
+/// [`Eth::new()`](../struct.Eth.html#method.new) does, and override
+/// the fields you need:
+///
+/// ```ignore
+/// let config = Config {
+///     tx_mode: TxMode::CutThrough(TxThreshold::Bytes64),
+///     ..Config::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// Speed of the MAC
+    pub speed: Speed,
+    /// Duplex mode of the MAC. In half-duplex mode, the MAC retries
+    /// transmission after a collision.
+    pub duplex: Duplex,
+    /// When the Rx DMA starts transferring a frame to memory
+    pub rx_mode: RxMode,
+    /// When the MAC starts transmitting a frame
+    pub tx_mode: TxMode,
+    /// Maximum number of beats in one Rx DMA transaction
+    pub rx_burst_length: BurstLength,
+    /// Maximum number of beats in one Tx DMA transaction
+    pub tx_burst_length: BurstLength,
+    /// Use only fixed-length bursts on the AHB bus
+    pub fixed_burst: bool,
+    /// Align bursts to the start address
+    pub address_aligned_beats: bool,
+    /// Whether the DMA arbiter alternates between Rx and Tx, or
+    /// always serves Rx first
+    pub arbitration: DmaArbitration,
+    /// How the DMA arbiter shares the bus between Rx and Tx with
+    /// [`DmaArbitration::RoundRobin`](enum.DmaArbitration.html#variant.RoundRobin).
+    /// The hardware cannot favor Tx, so `OneToOne` gives Tx the
+    /// largest share.
+    pub rx_tx_priority: PriorityRatio,
+    /// Verify IPv4 header and TCP/UDP/ICMP payload checksums of
+    /// received frames, and let the MAC insert them into frames
+    /// passed to `send()`. Insertion needs
+    /// [`TxMode::StoreAndForward`](enum.TxMode.html#variant.StoreAndForward).
+    pub checksum_offload: bool,
+    /// The MAC is connected to something that is not a standard
+    /// PHY, e.g. directly to a switch IC. The PHY is neither
+    /// identified nor reset, and `speed` and `duplex` are applied
+    /// as they are.
+    pub fixed_link: bool,
+    /// SMI address of the PHY. Defaults to the address at which the
+    /// PHY selected via feature usually resides.
+    pub phy_address: u8,
+    /// Identify and reset the PHY, and start auto-negotiation during
+    /// initialization. Disable this to bring up the PHY yourself via
+    /// [`Eth::get_phy()`](../struct.Eth.html#method.get_phy) later,
+    /// while the MAC and DMA are still configured.
+    pub phy_init: bool,
+    /// How many times to poll the PHY for auto-negotiation to
+    /// complete during initialization. Once it has, the MAC uses the
+    /// negotiated speed and duplex mode instead of `speed` and
+    /// `duplex`. With
+    /// [`Eth::new_with_delay()`](../struct.Eth.html#method.new_with_delay),
+    /// polls are 1 ms apart.
+    ///
+    /// With 0, initialization does not wait. Call
+    /// [`Eth::sync_mac_to_phy()`](../struct.Eth.html#method.sync_mac_to_phy)
+    /// when the link comes up instead, e.g. on a
+    /// [`LinkEvent`](../enum.LinkEvent.html).
+    pub autoneg_wait_polls: u32,
+    /// Deliver frames that were received with errors to software
+    pub forward_error_frames: bool,
+    /// Drop frames whose IP header or TCP/UDP/ICMP checksum failed
+    /// the checksum offload verification, instead of delivering
+    /// them with `ChecksumResult::Invalid`. Only
+    /// [`RxMode::StoreAndForward`](enum.RxMode.html#variant.StoreAndForward)
+    /// can drop them.
+    pub drop_checksum_error_frames: bool,
+    /// Deliver frames shorter than 64 bytes but without errors to
+    /// software
+    pub forward_undersized_good_frames: bool,
+    /// Which MAC control frames, e.g. pause frames, are passed to
+    /// software
+    pub pass_control_frames: PassControlFrames,
+    /// Minimum gap between transmitted frames. Shorter gaps than the
+    /// standard 96 bit times raise throughput, but are only
+    /// tolerated by some link partners. They apply in full-duplex
+    /// mode only.
+    pub inter_frame_gap: InterFrameGap,
+    /// Pause time that is requested in transmitted pause frames, in
+    /// units of 512 bit times (51.2 µs at 10 Mbps, 5.12 µs at
+    /// 100 Mbps)
+    pub pause_time: u16,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            speed: Speed::HundredMbps,
+            duplex: Duplex::Full,
+            rx_mode: RxMode::StoreAndForward,
+            tx_mode: TxMode::StoreAndForward,
+            rx_burst_length: BurstLength::Beats32,
+            tx_burst_length: BurstLength::Beats32,
+            fixed_burst: true,
+            address_aligned_beats: true,
+            arbitration: DmaArbitration::RoundRobin,
+            rx_tx_priority: PriorityRatio::TwoToOne,
+            checksum_offload: true,
+            fixed_link: false,
+            phy_address: crate::PHY_ADDR,
+            phy_init: true,
+            autoneg_wait_polls: 0,
+            forward_error_frames: false,
+            drop_checksum_error_frames: true,
+            forward_undersized_good_frames: false,
+            pass_control_frames: PassControlFrames::None,
+            inter_frame_gap: InterFrameGap::Bits96,
+            pause_time: 0x100,
+        }
+    }
+}
+
+/// Receive FIFO operation
+///
+/// Cut-through lowers latency, but the frame is already transferred
+/// to memory before the MAC has seen its end, so errored frames can
+/// no longer be dropped by hardware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RxMode {
+    /// Transfer a frame once it has been received completely
+    StoreAndForward,
+    /// Transfer a frame once the threshold is reached in the FIFO
+    CutThrough(RxThreshold),
+}
+
+/// Receive FIFO threshold for [`RxMode::CutThrough`](enum.RxMode.html#variant.CutThrough)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RxThreshold {
+    Bytes32,
+    Bytes64,
+    Bytes96,
+    Bytes128,
+}
+
+impl RxThreshold {
+    /// Value of the `rtc` field in `dmaomr`
+    pub(crate) fn bits(self) -> u8 {
+        match self {
+            RxThreshold::Bytes64 => 0b00,
+            RxThreshold::Bytes32 => 0b01,
+            RxThreshold::Bytes96 => 0b10,
+            RxThreshold::Bytes128 => 0b11,
+        }
+    }
+}
+
+/// Transmit FIFO operation
+///
+/// Cut-through lowers latency, but the MAC can only insert
+/// checksums into frames that are held completely in the FIFO.
+/// Checksum offload on transmit therefore requires store-and-forward.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxMode {
+    /// Start transmission once the whole frame is in the FIFO
+    StoreAndForward,
+    /// Start transmission once the threshold is reached in the FIFO
+    CutThrough(TxThreshold),
+}
+
+/// Transmit FIFO threshold for [`TxMode::CutThrough`](enum.TxMode.html#variant.CutThrough)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxThreshold {
+    Bytes16,
+    Bytes24,
+    Bytes32,
+    Bytes40,
+    Bytes64,
+    Bytes128,
+    Bytes192,
+    Bytes256,
+}
+
+impl TxThreshold {
+    /// Value of the `ttc` field in `dmaomr`
+    pub(crate) fn bits(self) -> u8 {
+        match self {
+            TxThreshold::Bytes64 => 0b000,
+            TxThreshold::Bytes128 => 0b001,
+            TxThreshold::Bytes192 => 0b010,
+            TxThreshold::Bytes256 => 0b011,
+            TxThreshold::Bytes40 => 0b100,
+            TxThreshold::Bytes32 => 0b101,
+            TxThreshold::Bytes24 => 0b110,
+            TxThreshold::Bytes16 => 0b111,
+        }
+    }
+}
+
+/// Interframe gap, in bit times
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterFrameGap {
+    /// Standard
+    Bits96,
+    Bits88,
+    Bits80,
+    Bits72,
+    Bits64,
+    Bits56,
+    Bits48,
+    Bits40,
+}
+
+impl InterFrameGap {
+    /// Value of the `ifg` field in `maccr`
+    pub(crate) fn bits(self) -> u8 {
+        match self {
+            InterFrameGap::Bits96 => 0b000,
+            InterFrameGap::Bits88 => 0b001,
+            InterFrameGap::Bits80 => 0b010,
+            InterFrameGap::Bits72 => 0b011,
+            InterFrameGap::Bits64 => 0b100,
+            InterFrameGap::Bits56 => 0b101,
+            InterFrameGap::Bits48 => 0b110,
+            InterFrameGap::Bits40 => 0b111,
+        }
+    }
+}
+
+/// Programmable burst length of the DMA engines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BurstLength {
+    Beats1,
+    Beats2,
+    Beats4,
+    Beats8,
+    Beats16,
+    Beats32,
+}
+
+impl BurstLength {
+    /// Value of the `pbl`/`rdp` fields in `dmabmr`
+    pub(crate) fn bits(self) -> u8 {
+        match self {
+            BurstLength::Beats1 => 1,
+            BurstLength::Beats2 => 2,
+            BurstLength::Beats4 => 4,
+            BurstLength::Beats8 => 8,
+            BurstLength::Beats16 => 16,
+            BurstLength::Beats32 => 32,
+        }
+    }
+}
+
+/// Arbitration scheme of the DMA engines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DmaArbitration {
+    /// Share the bus according to
+    /// [`Config::rx_tx_priority`](struct.Config.html#structfield.rx_tx_priority)
+    RoundRobin,
+    /// Tx only gets the bus when Rx does not need it
+    FixedRxPriority,
+}
+
+/// Rx:Tx priority ratio of the DMA arbiter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityRatio {
+    OneToOne,
+    TwoToOne,
+    ThreeToOne,
+    FourToOne,
+}
+
+impl PriorityRatio {
+    /// Value of the `pm` field in `dmabmr`
+    pub(crate) fn bits(self) -> u8 {
+        match self {
+            PriorityRatio::OneToOne => 0b00,
+            PriorityRatio::TwoToOne => 0b01,
+            PriorityRatio::ThreeToOne => 0b10,
+            PriorityRatio::FourToOne => 0b11,
+        }
+    }
+}
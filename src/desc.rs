@@ -39,6 +39,12 @@ impl Descriptor {
         self.r(n).read()
     }
 
+    /// All four descriptor words
+    #[cfg(any(test, feature = "debug-descriptors"))]
+    pub fn words(&self) -> [u32; 4] {
+        [self.read(0), self.read(1), self.read(2), self.read(3)]
+    }
+
     pub unsafe fn write(&mut self, n: usize, value: u32) {
         self.rw(n).write(value)
     }
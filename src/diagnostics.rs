@@ -0,0 +1,106 @@
+//! Counters and state for diagnostics
+
+#[cfg(feature = "stm32f107")]
+use stm32f1::stm32f107::ETHERNET_MMC;
+#[cfg(feature = "stm32f4xx")]
+use stm32f4xx_hal::stm32::ETHERNET_MMC;
+
+use crate::Eth;
+
+/// Counter reset, self-clearing
+const MMCCR_CR: u32 = 1 << 0;
+
+/// Missed frames counted by the controller (`dmamfbocr.mfc`)
+const DMAMFBOCR_MFC_MASK: u32 = 0xFFFF;
+/// Missed frames counted by the application (`dmamfbocr.mfa`)
+const DMAMFBOCR_MFA_SHIFT: u32 = 17;
+const DMAMFBOCR_MFA_MASK: u32 = 0x7FF;
+
+/// Snapshot of statistics and state, taken by
+/// [`Eth::diagnostics()`](../struct.Eth.html#method.diagnostics)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Diagnostics {
+    /// Good unicast frames received
+    pub rx_good_unicast: u32,
+    /// Frames received with CRC error
+    pub rx_crc_errors: u32,
+    /// Frames received with alignment error
+    pub rx_alignment_errors: u32,
+    /// Good frames transmitted
+    pub tx_good: u32,
+    /// Good frames transmitted after a single collision
+    pub tx_single_collisions: u32,
+    /// Good frames transmitted after more than one collision
+    pub tx_multiple_collisions: u32,
+    /// Frames dropped because no Rx descriptor was available, since
+    /// the previous snapshot
+    pub rx_missed_no_descriptor: u16,
+    /// Frames dropped because the Rx FIFO overflowed, since the
+    /// previous snapshot
+    pub rx_missed_fifo_overflow: u16,
+    /// PHY reports link, always `true` with
+    /// [`Config::fixed_link`](../struct.Config.html#structfield.fixed_link)
+    pub link_up: bool,
+    /// Rx DMA engine is running
+    pub rx_running: bool,
+    /// Tx DMA engine is running
+    pub tx_running: bool,
+}
+
+impl Diagnostics {
+    /// All error counters, by name
+    pub fn error_counters(&self) -> impl Iterator<Item = (&'static str, u32)> {
+        let counters = [
+            ("rx_crc_errors", self.rx_crc_errors),
+            ("rx_alignment_errors", self.rx_alignment_errors),
+            ("rx_missed_no_descriptor", self.rx_missed_no_descriptor.into()),
+            ("rx_missed_fifo_overflow", self.rx_missed_fifo_overflow.into()),
+        ];
+        (0..counters.len()).map(move |i| counters[i])
+    }
+}
+
+impl<'rx, 'tx> Eth<'rx, 'tx> {
+    /// Gather the MMC counters, missed frame counters, link status,
+    /// and DMA state in one go
+    ///
+    /// The MMC counters live in `ETHERNET_MMC`, which this driver
+    /// does not own, so it is borrowed from the caller. The missed
+    /// frame counters are cleared by reading, so they count the
+    /// frames since the previous call.
+    pub fn diagnostics(&self, mmc: &ETHERNET_MMC) -> Diagnostics {
+        // The MMC counters are only read, and not cleared on read
+        // with the reset value of `mmccr`.
+        let missed = self.eth_dma.dmamfbocr.read().bits();
+
+        Diagnostics {
+            rx_good_unicast: mmc.mmcrgufcr.read().bits(),
+            rx_crc_errors: mmc.mmcrfcecr.read().bits(),
+            rx_alignment_errors: mmc.mmcrfaecr.read().bits(),
+            tx_good: mmc.mmctgfcr.read().bits(),
+            tx_single_collisions: mmc.mmctgfsccr.read().bits(),
+            tx_multiple_collisions: mmc.mmctgfmsccr.read().bits(),
+            rx_missed_no_descriptor: (missed & DMAMFBOCR_MFC_MASK) as u16,
+            rx_missed_fifo_overflow: ((missed >> DMAMFBOCR_MFA_SHIFT) & DMAMFBOCR_MFA_MASK)
+                as u16,
+            link_up: if self.config.fixed_link {
+                self.is_link_up()
+            } else {
+                self.status().link_detected()
+            },
+            rx_running: self.rx_is_running(),
+            tx_running: self.tx_is_running(),
+        }
+    }
+
+    /// Zero all MMC counters at once, e.g. to start a measurement
+    ///
+    /// The missed frame counters are cleared as well. `mmc` is
+    /// borrowed as in [`diagnostics()`](#method.diagnostics).
+    pub fn reset_stats(&mut self, mmc: &ETHERNET_MMC) {
+        mmc.mmccr
+            .modify(|r, w| unsafe { w.bits(r.bits() | MMCCR_CR) });
+        // Reading clears the missed frame counters
+        self.eth_dma.dmamfbocr.read();
+    }
+}
@@ -0,0 +1,372 @@
+//! MAC frame filtering
+
+use crate::{Eth, MacAddress};
+
+/// Which bytes of an address a perfect filter slot ignores
+///
+/// Bit 0 masks the first byte of the address, bit 5 the last one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ByteMask(pub u8);
+
+impl ByteMask {
+    /// Compare all bytes
+    pub const NONE: ByteMask = ByteMask(0);
+}
+
+/// Which address of a received frame a perfect filter slot compares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressCompare {
+    /// Destination address
+    Destination,
+    /// Source address
+    Source,
+}
+
+/// Index into the 64 bit multicast hash table for `mac`
+///
+/// This is the upper 6 bits of the bit-reversed, inverted CRC-32 of
+/// the address, computed the same way as the MAC hardware does.
+pub fn multicast_hash_index(mac: impl Into<MacAddress>) -> u8 {
+    let mut crc: u32 = !0;
+    for &byte in mac.into().as_bytes().iter() {
+        let mut byte = byte;
+        for _ in 0..8 {
+            let mix = (crc ^ u32::from(byte)) & 1;
+            crc >>= 1;
+            if mix != 0 {
+                crc ^= 0xEDB8_8320;
+            }
+            byte >>= 1;
+        }
+    }
+    ((!crc).reverse_bits() >> 26) as u8
+}
+
+/// Contents of the multicast hash table
+///
+/// ```ignore
+/// let filter = MulticastFilter::new()
+///     .add(&[0x01, 0x00, 0x5E, 0x00, 0x00, 0xFB])
+///     .add(&[0x33, 0x33, 0x00, 0x00, 0x00, 0x01]);
+/// eth.set_multicast_hash(&filter);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MulticastFilter {
+    table: u64,
+}
+
+impl MulticastFilter {
+    /// Empty table, matching no address
+    pub fn new() -> Self {
+        MulticastFilter { table: 0 }
+    }
+
+    /// Also match `mac`, and any other address with the same hash
+    pub fn add(mut self, mac: impl Into<MacAddress>) -> Self {
+        self.table |= 1 << multicast_hash_index(mac);
+        self
+    }
+
+    /// Value of the `machthr` register
+    fn high(&self) -> u32 {
+        (self.table >> 32) as u32
+    }
+
+    /// Value of the `machtlr` register
+    fn low(&self) -> u32 {
+        self.table as u32
+    }
+}
+
+/// How multicast frames are filtered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MulticastMode {
+    /// Drop all multicast frames. This clears the hash table.
+    None,
+    /// Pass all multicast frames
+    All,
+    /// Pass multicast frames that match the hash table
+    Hash,
+    /// Pass multicast frames that match a perfect filter slot
+    Perfect,
+    /// Pass multicast frames that match either the hash table or a
+    /// perfect filter slot
+    HashOrPerfect,
+}
+
+impl MulticastMode {
+    /// Values of the `pam`, `hm`, and `hpf` bits in `macffr`
+    fn bits(self) -> (bool, bool, bool) {
+        match self {
+            MulticastMode::None => (false, true, false),
+            MulticastMode::All => (true, false, false),
+            MulticastMode::Hash => (false, true, false),
+            MulticastMode::Perfect => (false, false, false),
+            MulticastMode::HashOrPerfect => (false, true, true),
+        }
+    }
+}
+
+/// Which MAC control frames (e.g. pause frames) are passed to
+/// software
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PassControlFrames {
+    /// Pass no control frames
+    None,
+    /// Pass all control frames except pause frames
+    AllExceptPause,
+    /// Pass all control frames, even if they fail the address filter
+    All,
+    /// Pass control frames that pass the address filter
+    Filtered,
+}
+
+impl PassControlFrames {
+    /// Value of the `pcf` field in `macffr`
+    pub(crate) fn bits(self) -> u8 {
+        match self {
+            PassControlFrames::None => 0b00,
+            PassControlFrames::AllExceptPause => 0b01,
+            PassControlFrames::All => 0b10,
+            PassControlFrames::Filtered => 0b11,
+        }
+    }
+}
+
+/// Split an address into the values of the MACAxHR and MACAxLR
+/// address fields
+fn address_registers(addr: MacAddress) -> (u16, u32) {
+    let addr = addr.as_bytes();
+    let high = (u16::from(addr[5]) << 8) | u16::from(addr[4]);
+    let low = (u32::from(addr[3]) << 24)
+        | (u32::from(addr[2]) << 16)
+        | (u32::from(addr[1]) << 8)
+        | u32::from(addr[0]);
+    (high, low)
+}
+
+impl<'rx, 'tx> Eth<'rx, 'tx> {
+    /// Accept broadcast frames?
+    pub fn set_broadcast(&mut self, enabled: bool) {
+        self.eth_mac
+            .macffr
+            .modify(|_, w| w.bfd().bit(!enabled));
+    }
+
+    /// Select which MAC control frames are passed to software
+    ///
+    /// This applies in addition to
+    /// [`set_receive_all()`](#method.set_receive_all), so a bridge
+    /// can receive every frame and still choose what happens to
+    /// pause frames. Passed control frames are recognized with
+    /// [`RxPacket::is_control_frame()`](struct.RxPacket.html#method.is_control_frame).
+    pub fn set_pass_control_frames(&mut self, mode: PassControlFrames) {
+        self.config.pass_control_frames = mode;
+        self.eth_mac
+            .macffr
+            .modify(|_, w| unsafe { w.pcf().bits(mode.bits()) });
+    }
+
+    /// Pass all received frames to software, whether they match the
+    /// address filter or not
+    ///
+    /// Enabled by initialization. Control frames are still subject
+    /// to [`set_pass_control_frames()`](#method.set_pass_control_frames).
+    pub fn set_receive_all(&mut self, enabled: bool) {
+        self.eth_mac.macffr.modify(|_, w| w.ra().bit(enabled));
+    }
+
+    /// Select how multicast frames are filtered
+    ///
+    /// Note that the hash-or-perfect setting also applies to unicast
+    /// frames that are hash filtered.
+    ///
+    /// The filter has no effect while the MAC receives all frames.
+    pub fn set_multicast_mode(&mut self, mode: MulticastMode) {
+        if mode == MulticastMode::None {
+            self.set_multicast_hash(&MulticastFilter::new());
+        }
+        let (pam, hm, hpf) = mode.bits();
+        self.eth_mac
+            .macffr
+            .modify(|_, w| w.pam().bit(pam).hm().bit(hm).hpf().bit(hpf));
+    }
+
+    /// Program the multicast hash table
+    ///
+    /// The table is only consulted while hash filtering is enabled
+    /// for multicast frames.
+    pub fn set_multicast_hash(&mut self, filter: &MulticastFilter) {
+        self.eth_mac
+            .machthr
+            .write(|w| unsafe { w.bits(filter.high()) });
+        self.eth_mac
+            .machtlr
+            .write(|w| unsafe { w.bits(filter.low()) });
+    }
+
+    /// Program one of the additional perfect filter slots
+    ///
+    /// `index` selects the slot (`1..=3`), slot 0 holds the
+    /// station's own address. `mask` selects bytes that are not
+    /// compared. Passing `None` for `addr` disables the slot.
+    ///
+    /// The filters have no effect while the MAC receives all frames.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not in `1..=3`.
+    pub fn set_mac_address_filter(
+        &mut self,
+        index: usize,
+        addr: Option<MacAddress>,
+        mask: ByteMask,
+        compare: AddressCompare,
+    ) {
+        let enable = addr.is_some();
+        let (high, low) = address_registers(addr.unwrap_or_default());
+        let mbc = mask.0 & 0x3F;
+        let sa = compare == AddressCompare::Source;
+
+        match index {
+            1 => {
+                self.eth_mac
+                    .maca1lr
+                    .write(|w| unsafe { w.maca1l().bits(low) });
+                self.eth_mac.maca1hr.write(|w| unsafe {
+                    w.maca1h()
+                        .bits(high)
+                        .mbc()
+                        .bits(mbc)
+                        .sa()
+                        .bit(sa)
+                        .ae()
+                        .bit(enable)
+                });
+            }
+            2 => {
+                self.eth_mac
+                    .maca2lr
+                    .write(|w| unsafe { w.maca2l().bits(low) });
+                self.eth_mac.maca2hr.write(|w| unsafe {
+                    w.maca2h()
+                        .bits(high)
+                        .mbc()
+                        .bits(mbc)
+                        .sa()
+                        .bit(sa)
+                        .ae()
+                        .bit(enable)
+                });
+            }
+            3 => {
+                self.eth_mac
+                    .maca3lr
+                    .write(|w| unsafe { w.maca3l().bits(low) });
+                self.eth_mac.maca3hr.write(|w| unsafe {
+                    w.maca3h()
+                        .bits(high)
+                        .mbc()
+                        .bits(mbc)
+                        .sa()
+                        .bit(sa)
+                        .ae()
+                        .bit(enable)
+                });
+            }
+            _ => panic!("MAC address filter index out of range: {}", index),
+        }
+    }
+
+    /// Set the station's own MAC address in perfect filter slot 0
+    ///
+    /// Also used as the source address of pause frames.
+    pub fn set_mac_address(&mut self, addr: impl Into<MacAddress>) {
+        let (high, low) = address_registers(addr.into());
+        // The address is updated once the low register is written
+        self.eth_mac
+            .maca0hr
+            .write(|w| unsafe { w.maca0h().bits(high) });
+        self.eth_mac
+            .maca0lr
+            .write(|w| unsafe { w.maca0l().bits(low) });
+    }
+
+    /// Station address as programmed by
+    /// [`set_mac_address()`](#method.set_mac_address)
+    pub fn mac_address(&self) -> MacAddress {
+        let high = self.eth_mac.maca0hr.read().maca0h().bits();
+        let low = self.eth_mac.maca0lr.read().maca0l().bits();
+        let [a0, a1, a2, a3] = low.to_le_bytes();
+        let [a4, a5] = high.to_le_bytes();
+        MacAddress::new(a0, a1, a2, a3, a4, a5)
+    }
+
+    /// Only receive frames for `mac`, broadcasts, and the multicast
+    /// addresses in `extra_multicast`
+    ///
+    /// This programs the station address, the multicast hash table,
+    /// and the frame filter at once, and turns off receiving all
+    /// frames. As multicast frames are hash filtered, some frames for
+    /// other multicast addresses may pass.
+    pub fn listen_for(&mut self, mac: impl Into<MacAddress>, extra_multicast: &[MacAddress]) {
+        self.set_mac_address(mac);
+
+        if extra_multicast.is_empty() {
+            self.set_multicast_mode(MulticastMode::None);
+        } else {
+            let filter = extra_multicast
+                .iter()
+                .fold(MulticastFilter::new(), |filter, &addr| filter.add(addr));
+            self.set_multicast_hash(&filter);
+            self.set_multicast_mode(MulticastMode::Hash);
+        }
+
+        self.eth_mac.macffr.modify(|_, w| {
+            // Receive all
+            w.ra()
+                .clear_bit()
+                // Promiscuous mode
+                .pm()
+                .clear_bit()
+                // Hash unicast
+                .huc()
+                .clear_bit()
+                // Broadcast frames disable
+                .bfd()
+                .clear_bit()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{multicast_hash_index, MulticastFilter};
+
+    /// Hash indices as computed by Linux stmmac for the same
+    /// addresses, `bitrev32(~crc32_le(~0, addr, 6)) >> 26`
+    const KNOWN_INDICES: [([u8; 6], u8); 4] = [
+        ([0x01, 0x00, 0x5E, 0x00, 0x00, 0x01], 32),
+        ([0x01, 0x00, 0x5E, 0x00, 0x00, 0xFB], 48),
+        ([0x33, 0x33, 0x00, 0x00, 0x00, 0x01], 1),
+        ([0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF], 0),
+    ];
+
+    #[test]
+    fn hash_index_matches_known_vectors() {
+        for &(mac, index) in KNOWN_INDICES.iter() {
+            assert_eq!(multicast_hash_index(mac), index, "index of {:02x?}", mac);
+        }
+    }
+
+    #[test]
+    fn filter_splits_table_into_registers() {
+        // 01:00:5E:00:00:01 is bit 0 of the high register,
+        // 33:33:00:00:00:01 bit 1 of the low one
+        let filter = MulticastFilter::new()
+            .add(KNOWN_INDICES[0].0)
+            .add(KNOWN_INDICES[2].0);
+        assert_eq!(filter.high(), 1 << 0, "machthr");
+        assert_eq!(filter.low(), 1 << 1, "machtlr");
+    }
+}
@@ -1,16 +1,21 @@
 #![no_std]
 
+use embedded_hal::blocking::delay::DelayUs;
 #[cfg(feature = "stm32f107")]
 use stm32f1xx_hal::stm32::{Interrupt, ETHERNET_DMA, ETHERNET_MAC, NVIC};
 #[cfg(feature = "stm32f4xx")]
 use stm32f4xx_hal::stm32::{Interrupt, ETHERNET_DMA, ETHERNET_MAC, NVIC};
 
 // If no phy specified, print error message.
-#[cfg(not(any(feature = "dp83848", feature = "lan8742",)))]
+#[cfg(not(any(feature = "dp83848", feature = "lan8742", feature = "ksz8081")))]
 compile_error!("Phy not specified. A `--features <phy-name>` is required.");
 
 // If any two or more phy:s are specified, print error message.
-#[cfg(all(feature = "dp83848", feature = "lan8742"))]
+#[cfg(any(
+    all(feature = "dp83848", feature = "lan8742"),
+    all(feature = "dp83848", feature = "ksz8081"),
+    all(feature = "lan8742", feature = "ksz8081"),
+))]
 compile_error!("Multiple Phy:s specified. Only a single `--features <phy-name>` can be specified.");
 
 #[cfg(feature = "lan8742")]
@@ -27,20 +32,68 @@ pub use phy_dp83848 as phy;
 #[cfg(feature = "dp83848")]
 use phy_dp83848::{Phy, PhyStatus};
 
+#[cfg(feature = "ksz8081")]
+pub mod phy_ksz8081;
+#[cfg(feature = "ksz8081")]
+pub use phy_ksz8081 as phy;
+#[cfg(feature = "ksz8081")]
+use phy_ksz8081::{Phy, PhyStatus};
+
+mod phy_common;
+pub use phy::{Duplex, Speed};
 mod ring;
 mod smi;
-pub use ring::RingEntry;
+pub use ring::{RingEntry, RunningState};
+mod region;
+pub use region::DmaRegion;
+mod checksum;
 mod desc;
 mod rx;
-pub use rx::{RxDescriptor, RxError};
-use rx::{RxPacket, RxRing, RxRingEntry};
+pub use rx::{ChecksumResult, FrameType, RxDescriptor, RxError, RxPacket};
+use rx::{RxRing, RxRingEntry};
 mod tx;
-pub use tx::{TxDescriptor, TxError};
+pub use tx::{TxChecksum, TxCompletions, TxDescriptor, TxError, TxSlot};
 use tx::{TxRing, TxRingEntry};
+mod config;
+pub use config::{
+    BurstLength, Config, DmaArbitration, InterFrameGap, PriorityRatio, RxMode, RxThreshold, TxMode,
+    TxThreshold,
+};
+mod filter;
+pub use filter::{
+    multicast_hash_index, AddressCompare, ByteMask, MulticastFilter, MulticastMode,
+    PassControlFrames,
+};
+mod link;
+pub use link::{LinkEvent, LinkMonitor};
+mod mac_address;
+pub use mac_address::{MacAddress, ParseMacAddressError};
+mod diagnostics;
+pub use diagnostics::Diagnostics;
+mod vlan;
+mod source_address;
+mod reinit;
+pub use source_address::SaMode;
+#[cfg(feature = "stm32f4xx")]
+mod mac_debug;
+#[cfg(feature = "stm32f4xx")]
+pub use mac_debug::{
+    MacDebugStatus, RxFifoLevel, RxFifoReadState, TxFifoReadState, TxFrameState,
+};
 mod setup;
-pub use setup::setup;
+pub use setup::{reset_phy, setup, MediaInterface};
 #[cfg(feature = "nucleo-f429zi")]
 pub use setup::setup_pins;
+#[cfg(feature = "stm32f4xx")]
+pub mod pins;
+
+#[cfg(feature = "async")]
+mod asynch;
+
+#[cfg(feature = "capture")]
+mod capture;
+#[cfg(feature = "capture")]
+pub use capture::{CapturedFrame, CAPTURE_MAX_BYTES, CAPTURE_MAX_FRAMES};
 
 #[cfg(feature = "smoltcp-phy")]
 pub use smoltcp;
@@ -53,19 +106,77 @@ pub use smoltcp_phy::{EthRxToken, EthTxToken};
 const PHY_ADDR: u8 = 0;
 #[cfg(feature = "dp83848")]
 const PHY_ADDR: u8 = 1;
+#[cfg(feature = "ksz8081")]
+const PHY_ADDR: u8 = 0;
 
 /// From the datasheet: *VLAN Frame maxsize = 1522*
 const MTU: usize = 1522;
+/// Largest untagged frame without FCS: `MTU` less the VLAN tag and
+/// the FCS
+const MAX_FRAME_LEN: usize = MTU - 4 - 4;
+
+/// How many times to poll for completion of the MAC reset
+const MAC_RESET_TIMEOUT_POLLS: u32 = 1_000_000;
+
+/// How many times to poll for the DMA engines to stop
+const DMA_STOP_TIMEOUT_POLLS: u32 = 1_000_000;
+
+/// Settle time after a PHY reset, if a delay provider was given
+pub(crate) const PHY_RESET_SETTLE_US: u32 = 1_000;
+
+/// Interval between auto-negotiation polls, if a delay provider was
+/// given
+const AUTONEG_POLL_INTERVAL_US: u32 = 1_000;
+
+/// Time stamp trigger status in `dmasr`, read-only
+const DMASR_TSTS: u32 = 1 << 29;
+
+/// Errors that can occur while initializing the Ethernet hardware
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitError {
+    /// The PHY identifier registers do not match the PHY selected
+    /// via feature. This usually means that the wrong
+    /// `--features <phy-name>` was chosen for this board.
+    UnexpectedPhy(phy::PhyId),
+    /// The MAC did not come out of reset. The Ethernet clocks may
+    /// be misconfigured, or the PHY does not provide a reference
+    /// clock.
+    MacResetTimeout,
+    /// The PHY did not complete its software reset
+    PhyResetTimeout,
+    /// A ring entry is not aligned to a word boundary
+    MisalignedBuffer,
+    /// A ring entry resides in memory that the DMA engine cannot
+    /// access, such as Core-Coupled Memory (CCM)
+    InaccessibleBuffer,
+}
+
+/// Compile-time check of the ring sizes for
+/// [`Eth::new_with_arrays()`](struct.Eth.html#method.new_with_arrays)
+struct RingSize<const N: usize>;
+
+impl<const N: usize> RingSize<N> {
+    const CHECK: () = assert!(N >= 2, "A ring needs at least 2 entries");
+}
 
 /// Ethernet driver for *STM32* chips.
 /// [`Phy`](phy/struct.Phy.html) can be selected via feature as:
 /// *lan8742* (e.g. on STM Nucleo-144 boards)
 /// *dp83848*
+/// *ksz8081*
 pub struct Eth<'rx, 'tx> {
     eth_mac: ETHERNET_MAC,
     eth_dma: ETHERNET_DMA,
     rx_ring: RxRing<'rx>,
     tx_ring: TxRing<'tx>,
+    phy_addr: u8,
+    config: Config,
+    link_up: bool,
+    source_address_mode: SaMode,
+    link_monitor: LinkMonitor,
+    autoneg_attempts: u32,
+    #[cfg(feature = "capture")]
+    capture: capture::CaptureRing,
 }
 
 impl<'rx, 'tx> Eth<'rx, 'tx> {
@@ -76,31 +187,232 @@ impl<'rx, 'tx> Eth<'rx, 'tx> {
     ///
     /// Make sure that the buffers reside in a memory region that is
     /// accessible by the peripheral. Core-Coupled Memory (CCM) is
-    /// usually not.
+    /// usually not. A [`DmaRegion`](struct.DmaRegion.html) can be
+    /// placed in a suitable region with a single `#[link_section]`.
     ///
     /// Other than that, initializes and starts the Ethernet hardware
     /// so that you can [`send()`](#method.send) and
     /// [`recv_next()`](#method.recv_next).
+    ///
+    /// Fails if a ring entry is misaligned or not DMA-accessible,
+    /// instead of hanging if the MAC or the PHY do not come out of
+    /// reset, and if the PHY does not identify as the one selected
+    /// via feature.
     pub fn new(
         eth_mac: ETHERNET_MAC,
         eth_dma: ETHERNET_DMA,
         rx_buffer: &'rx mut [RxRingEntry],
         tx_buffer: &'tx mut [TxRingEntry],
-    ) -> Self {
+    ) -> Result<Self, InitError> {
+        Self::new_with_config(eth_mac, eth_dma, rx_buffer, tx_buffer, Config::default())
+    }
+
+    /// Like [`new()`](#method.new), but takes the rings as arrays so
+    /// that their sizes are checked at compile time: each ring needs
+    /// at least 2 entries.
+    ///
+    /// ```ignore
+    /// static mut RX_RING: [RxRingEntry; 4] = ...;
+    /// let eth = Eth::new_with_arrays(mac, dma, &mut RX_RING, &mut TX_RING)?;
+    /// ```
+    pub fn new_with_arrays<const N: usize, const M: usize>(
+        eth_mac: ETHERNET_MAC,
+        eth_dma: ETHERNET_DMA,
+        rx_buffer: &'rx mut [RxRingEntry; N],
+        tx_buffer: &'tx mut [TxRingEntry; M],
+    ) -> Result<Self, InitError> {
+        #[allow(clippy::let_unit_value)]
+        let () = RingSize::<N>::CHECK;
+        #[allow(clippy::let_unit_value)]
+        let () = RingSize::<M>::CHECK;
+        Self::new(eth_mac, eth_dma, &mut rx_buffer[..], &mut tx_buffer[..])
+    }
+
+    /// Like [`new()`](#method.new), but applies the settings in
+    /// [`Config`](struct.Config.html) instead of the defaults.
+    pub fn new_with_config(
+        eth_mac: ETHERNET_MAC,
+        eth_dma: ETHERNET_DMA,
+        rx_buffer: &'rx mut [RxRingEntry],
+        tx_buffer: &'tx mut [TxRingEntry],
+        config: Config,
+    ) -> Result<Self, InitError> {
+        Self::new_inner(eth_mac, eth_dma, rx_buffer, tx_buffer, config, None)
+    }
+
+    /// Like [`new_with_config()`](#method.new_with_config), but
+    /// waits with `delay` where the hardware needs time to settle:
+    /// after the PHY reset, and between polls for auto-negotiation
+    /// (see [`Config::autoneg_wait_polls`](struct.Config.html#structfield.autoneg_wait_polls)).
+    ///
+    /// Without it, initialization relies on the polling loops being
+    /// slow enough, which may not hold for slow PHYs.
+    pub fn new_with_delay<D: DelayUs<u32>>(
+        eth_mac: ETHERNET_MAC,
+        eth_dma: ETHERNET_DMA,
+        rx_buffer: &'rx mut [RxRingEntry],
+        tx_buffer: &'tx mut [TxRingEntry],
+        config: Config,
+        delay: &mut D,
+    ) -> Result<Self, InitError> {
+        Self::new_inner(eth_mac, eth_dma, rx_buffer, tx_buffer, config, Some(delay))
+    }
+
+    fn new_inner(
+        eth_mac: ETHERNET_MAC,
+        eth_dma: ETHERNET_DMA,
+        rx_buffer: &'rx mut [RxRingEntry],
+        tx_buffer: &'tx mut [TxRingEntry],
+        config: Config,
+        delay: Option<&mut dyn DelayUs<u32>>,
+    ) -> Result<Self, InitError> {
+        for entry in rx_buffer.iter() {
+            entry.validate()?;
+        }
+        for entry in tx_buffer.iter() {
+            entry.validate()?;
+        }
+
         let mut eth = Eth {
             eth_mac,
             eth_dma,
             rx_ring: RxRing::new(rx_buffer),
             tx_ring: TxRing::new(tx_buffer),
+            phy_addr: config.phy_address,
+            config,
+            link_up: config.fixed_link,
+            source_address_mode: SaMode::Unchanged,
+            link_monitor: LinkMonitor::new(),
+            autoneg_attempts: 0,
+            #[cfg(feature = "capture")]
+            capture: capture::CaptureRing::new(),
         };
-        eth.init();
-        eth.rx_ring.start(&eth.eth_dma);
-        eth.tx_ring.start(&eth.eth_dma);
-        eth
+        eth.rx_ring.set_checksum_offload(config.checksum_offload);
+        eth.tx_ring.set_checksum_insertion(config.checksum_offload);
+        eth.init(delay)?;
+        eth.start();
+        Ok(eth)
+    }
+
+    /// Start the DMA engines and enable the MAC
+    ///
+    /// Both rings are set up from scratch: received packets that
+    /// have not been consumed, and packets that were queued for
+    /// sending, are discarded.
+    pub fn start(&mut self) {
+        self.eth_mac
+            .maccr
+            .modify(|_, w| w.re().set_bit().te().set_bit());
+        self.rx_ring.start(&self.eth_dma);
+        self.tx_ring.start(&self.eth_dma);
+    }
+
+    /// Stop the DMA engines and disable the MAC
+    ///
+    /// Waits for the frames in flight to be completed. Stop before
+    /// changing MAC settings, then [`start()`](#method.start) again.
+    ///
+    /// Fails with `Timeout` if a DMA engine is still running after
+    /// the wait. The MAC is disabled and the Tx FIFO flushed
+    /// nevertheless.
+    pub fn stop(&mut self) -> Result<(), phy::Timeout> {
+        // Let the transmitter finish the current frame
+        self.tx_ring.stop(&self.eth_dma);
+        let tx_stopped =
+            (0..DMA_STOP_TIMEOUT_POLLS).any(|_| !self.tx_ring.is_running(&self.eth_dma));
+        self.eth_mac
+            .maccr
+            .modify(|_, w| w.re().clear_bit().te().clear_bit());
+
+        self.rx_ring.stop(&self.eth_dma);
+        let rx_stopped = (0..DMA_STOP_TIMEOUT_POLLS)
+            .any(|_| !self.rx_ring.running_state(&self.eth_dma).is_running());
+
+        // Flush transmit FIFO
+        self.eth_dma.dmaomr.modify(|_, w| w.ftf().set_bit());
+
+        if tx_stopped && rx_stopped {
+            Ok(())
+        } else {
+            Err(phy::Timeout)
+        }
+    }
+
+    /// Enable or disable the MAC receiver
+    ///
+    /// Unlike [`stop()`](#method.stop), this leaves the DMA engine
+    /// and the Rx ring alone. A frame that is being received when
+    /// the receiver is disabled is still completed.
+    pub fn set_receiver_enabled(&mut self, enabled: bool) {
+        self.eth_mac.maccr.modify(|_, w| w.re().bit(enabled));
+    }
+
+    /// Enable or disable the MAC transmitter
+    ///
+    /// Unlike [`stop()`](#method.stop), this leaves the DMA engine
+    /// and the Tx ring alone. The current frame is transmitted
+    /// before the transmitter is disabled.
+    pub fn set_transmitter_enabled(&mut self, enabled: bool) {
+        self.eth_mac.maccr.modify(|_, w| w.te().bit(enabled));
+    }
+
+    /// Strip the FCS from received frames?
+    ///
+    /// It is stripped by default, along with the padding of short
+    /// IEEE 802.3 length frames. Disable this to receive the raw
+    /// frame including its FCS, e.g. for packet capture.
+    /// [`RxPacket::len()`](struct.RxPacket.html#method.len) then
+    /// includes the FCS.
+    pub fn set_crc_stripping(&mut self, enabled: bool) {
+        #[cfg(feature = "stm32f4xx")]
+        self.eth_mac
+            .maccr
+            .modify(|_, w| w.apcs().bit(enabled).cstf().bit(enabled));
+        #[cfg(feature = "stm32f107")]
+        self.eth_mac.maccr.modify(|_, w| w.apcs().bit(enabled));
+        self.rx_ring.set_crc_stripping(enabled);
+    }
+
+    /// Let the MAC pad frames shorter than 60 bytes to the minimum
+    /// frame length?
+    ///
+    /// Enabled by default. Disable it to transmit runt frames, e.g.
+    /// for testing. The FCS is still appended.
+    pub fn set_tx_padding(&mut self, enabled: bool) {
+        self.tx_ring.set_padding(enabled);
     }
 
-    fn init(&mut self) -> &Self {
-        self.reset_mac_and_wait();
+    /// Enable or disable the receive watchdog
+    ///
+    /// The watchdog cuts off received frames after 2048 bytes. With
+    /// it disabled, frames of up to 16384 bytes are accepted, e.g. to
+    /// monitor oversized frames. Those that do not fit into one ring
+    /// buffer are still reported as `RxError::Truncated`.
+    pub fn set_watchdog(&mut self, enabled: bool) {
+        self.eth_mac.maccr.modify(|_, w| w.wd().bit(!enabled));
+    }
+
+    /// Enable or disable the transmit jabber timer
+    ///
+    /// The jabber timer cuts off transmitted frames after 2048
+    /// bytes. With it disabled, frames of up to 16384 bytes can be
+    /// sent.
+    pub fn set_jabber(&mut self, enabled: bool) {
+        self.eth_mac.maccr.modify(|_, w| w.jd().bit(!enabled));
+    }
+
+    /// Loop transmitted frames back to the receiver inside the MAC
+    ///
+    /// Frames are then not sent to the PHY, which still has to
+    /// provide the clocks. Use full-duplex mode, and make sure the
+    /// frames pass the address filter, e.g. by sending them to the
+    /// [station address](#method.set_mac_address).
+    pub fn set_loopback(&mut self, enabled: bool) {
+        self.eth_mac.maccr.modify(|_, w| w.lm().bit(enabled));
+    }
+
+    fn init(&mut self, mut delay: Option<&mut dyn DelayUs<u32>>) -> Result<(), InitError> {
+        self.reset_mac_and_wait()?;
 
         // set clock range in MAC MII address register
         #[cfg(feature = "stm32f107")]
@@ -112,9 +424,35 @@ impl<'rx, 'tx> Eth<'rx, 'tx> {
             self.eth_mac.macmiiar.modify(|_, w| w.cr().cr_20_35());
         }
 
-        self.get_phy().reset().set_autoneg();
+        if self.config.phy_init && !self.config.fixed_link {
+            let phy_id = self.get_phy().identify();
+            if !phy::is_supported(&phy_id) {
+                return Err(InitError::UnexpectedPhy(phy_id));
+            }
+            self.get_phy()
+                .reset()
+                .map_err(|_| InitError::PhyResetTimeout)?;
+            if let Some(delay) = delay.as_mut() {
+                delay.delay_us(PHY_RESET_SETTLE_US);
+            }
+            self.restart_autoneg();
+
+            for _ in 0..self.config.autoneg_wait_polls {
+                if let Some((speed, duplex)) = self.negotiated_link() {
+                    self.config.speed = speed;
+                    self.config.duplex = duplex;
+                    break;
+                }
+                if let Some(delay) = delay.as_mut() {
+                    delay.delay_us(AUTONEG_POLL_INTERVAL_US);
+                }
+            }
+        }
 
         // Configuration Register
+        let fast_ethernet = self.config.speed == Speed::HundredMbps;
+        let full_duplex = self.config.duplex == Duplex::Full;
+        let checksum_offload = self.config.checksum_offload;
         #[cfg(feature = "stm32f4xx")]
         self.eth_mac.maccr.modify(|_, w| {
             // CRC stripping for Type frames
@@ -122,37 +460,40 @@ impl<'rx, 'tx> Eth<'rx, 'tx> {
                 .set_bit()
                 // Fast Ethernet speed
                 .fes()
-                .set_bit()
+                .bit(fast_ethernet)
                 // Duplex mode
                 .dm()
-                .set_bit()
+                .bit(full_duplex)
                 // Automatic pad/CRC stripping
                 .apcs()
                 .set_bit()
-                // Retry disable in half-duplex mode
+                // Retry disable, only relevant in half-duplex mode
                 .rd()
-                .set_bit()
+                .bit(full_duplex)
                 // Receiver enable
                 .re()
                 .set_bit()
                 // Transmitter enable
                 .te()
                 .set_bit()
+                // Checksum offload
+                .ipco()
+                .bit(checksum_offload)
         });
         #[cfg(feature = "stm32f107")]
         self.eth_mac.maccr.modify(|_, w| {
             // Fast Ethernet speed
             w.fes()
-                .set_bit()
+                .bit(fast_ethernet)
                 // Duplex mode
                 .dm()
-                .set_bit()
+                .bit(full_duplex)
                 // Automatic pad/CRC stripping
                 .apcs()
                 .set_bit()
-                // Retry disable in half-duplex mode
+                // Retry disable, only relevant in half-duplex mode
                 .rd()
-                .set_bit()
+                .bit(full_duplex)
                 // Receiver enable
                 .re()
                 .set_bit()
@@ -161,75 +502,106 @@ impl<'rx, 'tx> Eth<'rx, 'tx> {
                 .set_bit()
                 // Checksum offload
                 .ipco()
-                .set_bit()
+                .bit(checksum_offload)
         });
+        // Interframe gap
+        let ifg = self.config.inter_frame_gap.bits();
+        self.eth_mac
+            .maccr
+            .modify(|_, w| unsafe { w.ifg().bits(ifg) });
 
         // frame filter register
-        self.eth_mac.macffr.modify(|_, w| {
+        let pass_control_frames = self.config.pass_control_frames.bits();
+        self.eth_mac.macffr.modify(|_, w| unsafe {
             // Receive All
             w.ra()
                 .set_bit()
                 // Promiscuous mode
                 .pm()
                 .set_bit()
+                // Pass control frames
+                .pcf()
+                .bits(pass_control_frames)
         });
         // Flow Control Register
+        let pause_time = self.config.pause_time;
         self.eth_mac.macfcr.modify(|_, w| {
             // Pause time
-            w.pt().bits(0x100)
+            w.pt().bits(pause_time)
         });
         // operation mode register
-        self.eth_dma.dmaomr.modify(|_, w| {
+        let config = self.config;
+        let forward_error_frames = config.forward_error_frames || self.rx_ring.crc_errors();
+        self.eth_dma.dmaomr.modify(|_, w| unsafe {
             // Dropping of TCP/IP checksum error frames disable
-            w.dtcefd()
-                .set_bit()
-                // Receive store and forward
-                .rsf()
-                .set_bit()
+            let w = w
+                .dtcefd()
+                .bit(!config.drop_checksum_error_frames)
                 // Disable flushing of received frames
                 .dfrf()
                 .set_bit()
-                // Transmit store and forward
-                .tsf()
-                .set_bit()
                 // Forward error frames
                 .fef()
-                .set_bit()
+                .bit(forward_error_frames)
+                // Forward undersized good frames
+                .fugf()
+                .bit(config.forward_undersized_good_frames)
                 // Operate on second frame
                 .osf()
-                .set_bit()
+                .set_bit();
+            let w = match config.rx_mode {
+                // Receive store and forward
+                RxMode::StoreAndForward => w.rsf().set_bit(),
+                // Receive threshold control
+                RxMode::CutThrough(threshold) => w.rsf().clear_bit().rtc().bits(threshold.bits()),
+            };
+            match config.tx_mode {
+                // Transmit store and forward
+                TxMode::StoreAndForward => w.tsf().set_bit(),
+                // Transmit threshold control
+                TxMode::CutThrough(threshold) => w.tsf().clear_bit().ttc().bits(threshold.bits()),
+            }
         });
         // bus mode register
         self.eth_dma.dmabmr.modify(|_, w| unsafe {
             // Address-aligned beats
             w.aab()
-                .set_bit()
+                .bit(config.address_aligned_beats)
                 // Fixed burst
                 .fb()
-                .set_bit()
+                .bit(config.fixed_burst)
                 // Rx DMA PBL
                 .rdp()
-                .bits(32)
+                .bits(config.rx_burst_length.bits())
                 // Programmable burst length
                 .pbl()
-                .bits(32)
-                // Rx Tx priority ratio 2:1
+                .bits(config.tx_burst_length.bits())
+                // Rx Tx priority ratio
                 .pm()
-                .bits(0b01)
+                .bits(config.rx_tx_priority.bits())
                 // Use separate PBL
                 .usp()
                 .set_bit()
+                // DMA arbitration
+                .da()
+                .bit(config.arbitration == DmaArbitration::FixedRxPriority)
         });
 
-        self
+        Ok(())
     }
 
     /// reset all MAC subsystem internal registers and logic
-    fn reset_mac_and_wait(&self) {
+    fn reset_mac_and_wait(&self) -> Result<(), InitError> {
         self.eth_dma.dmabmr.modify(|_, w| w.sr().set_bit());
 
         // Wait until done
-        while self.eth_dma.dmabmr.read().sr().bit_is_set() {}
+        for _ in 0..MAC_RESET_TIMEOUT_POLLS {
+            if self.eth_dma.dmabmr.read().sr().bit_is_clear() {
+                return Ok(());
+            }
+        }
+
+        Err(InitError::MacResetTimeout)
     }
 
     /// Enable RX and TX interrupts
@@ -239,6 +611,23 @@ impl<'rx, 'tx> Eth<'rx, 'tx> {
     /// clear interrupt pending bits. Otherwise the interrupt will
     /// reoccur immediately.
     pub fn enable_interrupt(&self) {
+        self.enable_dma_interrupts();
+
+        // Enable ethernet interrupts
+        let interrupt = Interrupt::ETH;
+
+        unsafe {
+            NVIC::unmask(interrupt);
+        }
+    }
+
+    /// Enable RX and TX interrupts in the peripheral only
+    ///
+    /// Like [`enable_interrupt()`](#method.enable_interrupt), but
+    /// leaves the `ETH` interrupt in the NVIC alone. Use this with
+    /// frameworks such as RTIC that manage masking and priorities
+    /// themselves.
+    pub fn enable_dma_interrupts(&self) {
         self.eth_dma.dmaier.modify(|_, w| {
             w
                 // Normal interrupt summary enable
@@ -251,23 +640,77 @@ impl<'rx, 'tx> Eth<'rx, 'tx> {
                 .tie()
                 .set_bit()
         });
+    }
 
-        // Enable ethernet interrupts
-        let interrupt = Interrupt::ETH;
+    /// Interrupt as soon as the Rx DMA has filled the first buffer
+    /// of a frame, before the frame has been received completely
+    ///
+    /// Reported as `InterruptReason::early_rx`. Use together with
+    /// [`RxMode::CutThrough`](enum.RxMode.html#variant.CutThrough)
+    /// to start processing a frame header early.
+    pub fn set_early_rx_interrupt(&mut self, enabled: bool) {
+        self.eth_dma.dmaier.modify(|_, w| w.erie().bit(enabled));
+    }
 
-        unsafe {
-            NVIC::unmask(interrupt);
-        }
+    /// Is a receive interrupt pending?
+    ///
+    /// Unlike [`interrupt_handler()`](#method.interrupt_handler),
+    /// this does not acknowledge the interrupt.
+    pub fn rx_interrupt_pending(&self) -> bool {
+        self.eth_dma.dmasr.read().rs().bit_is_set()
+    }
+
+    /// Is a transmit interrupt pending?
+    ///
+    /// Unlike [`interrupt_handler()`](#method.interrupt_handler),
+    /// this does not acknowledge the interrupt.
+    pub fn tx_interrupt_pending(&self) -> bool {
+        self.eth_dma.dmasr.read().ts().bit_is_set()
     }
 
     /// Calls [`eth_interrupt_handler()`](fn.eth_interrupt_handler.html)
-    pub fn interrupt_handler(&self) {
-        eth_interrupt_handler(&self.eth_dma);
+    pub fn interrupt_handler(&self) -> InterruptReason {
+        eth_interrupt_handler(&self.eth_dma)
     }
 
     /// Construct a PHY driver
     pub fn get_phy<'a>(&'a self) -> Phy<'a> {
-        Phy::new(&self.eth_mac.macmiiar, &self.eth_mac.macmiidr, PHY_ADDR)
+        Phy::new(&self.eth_mac.macmiiar, &self.eth_mac.macmiidr, self.phy_addr)
+    }
+
+    /// Scan all SMI addresses for a PHY of the selected type
+    ///
+    /// Returns the first address at which the identifier registers
+    /// match. That address is then used by all further PHY accesses
+    /// through [`get_phy()`](#method.get_phy).
+    pub fn detect_phy_address(&mut self) -> Option<u8> {
+        let addr = (0..=31).find(|&addr| {
+            Phy::new(&self.eth_mac.macmiiar, &self.eth_mac.macmiidr, addr).is_present()
+        })?;
+        self.phy_addr = addr;
+        Some(addr)
+    }
+
+    /// SMI address used by [`get_phy()`](#method.get_phy)
+    pub fn phy_address(&self) -> u8 {
+        self.phy_addr
+    }
+
+    /// Use the PHY at SMI address `addr` from now on, e.g. one found
+    /// by [`detect_phy_address()`](#method.detect_phy_address)
+    ///
+    /// To have initialization use it as well, pass it in
+    /// [`Config::phy_address`](struct.Config.html#structfield.phy_address),
+    /// or bring up the PHY yourself after initializing with
+    /// [`Config::phy_init`](struct.Config.html#structfield.phy_init)
+    /// disabled.
+    ///
+    /// # Panics
+    ///
+    /// If `addr` is not in `0..=31`.
+    pub fn set_phy_address(&mut self, addr: u8) {
+        assert!(addr <= 31, "PHY address out of range");
+        self.phy_addr = addr;
     }
 
     /// Obtain PHY status
@@ -275,6 +718,108 @@ impl<'rx, 'tx> Eth<'rx, 'tx> {
         self.get_phy().status()
     }
 
+    /// Link state as last read from the PHY by
+    /// [`update_link_state()`](#method.update_link_state) or
+    /// [`sync_mac_to_phy()`](#method.sync_mac_to_phy)
+    ///
+    /// This does not access the PHY, so it is cheap enough for hot
+    /// loops. Always `true` with
+    /// [`Config::fixed_link`](struct.Config.html#structfield.fixed_link).
+    pub fn is_link_up(&self) -> bool {
+        self.link_up
+    }
+
+    /// Read the link state from the PHY and cache it for
+    /// [`is_link_up()`](#method.is_link_up)
+    ///
+    /// Call this periodically, or when the PHY signals a link change
+    /// on its interrupt pin.
+    pub fn update_link_state(&mut self) -> bool {
+        if !self.config.fixed_link {
+            self.link_up = self.status().link_detected();
+        }
+        self.link_up
+    }
+
+    /// Configure the MAC for the speed and duplex mode that the PHY
+    /// has negotiated
+    ///
+    /// Call this when the link comes up, e.g. after a cable has been
+    /// reconnected to a port with different capabilities. Returns
+    /// `false`, leaving the MAC unchanged, if the PHY has no link,
+    /// has not completed auto-negotiation, or does not report the
+    /// link parameters.
+    pub fn sync_mac_to_phy(&mut self) -> bool {
+        match self.negotiated_link() {
+            Some((speed, duplex)) => {
+                self.set_speed_and_duplex(speed, duplex);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Enable and restart auto-negotiation in the PHY
+    ///
+    /// Counts towards
+    /// [`autoneg_attempts()`](#method.autoneg_attempts). Call
+    /// [`sync_mac_to_phy()`](#method.sync_mac_to_phy) once it has
+    /// completed.
+    pub fn restart_autoneg(&mut self) {
+        self.get_phy().set_autoneg();
+        self.autoneg_attempts = self.autoneg_attempts.wrapping_add(1);
+    }
+
+    /// How many times auto-negotiation has been started, by
+    /// initialization, [`reinit()`](#method.reinit), or
+    /// [`restart_autoneg()`](#method.restart_autoneg)
+    ///
+    /// Auto-negotiation started on the PHY directly, through
+    /// [`get_phy()`](#method.get_phy) and for example `set_autoneg()`
+    /// or `set_advertised()`, is not counted.
+    ///
+    /// A count that keeps rising without
+    /// [`PhyStatus::autoneg_done()`](phy/struct.PhyStatus.html#method.autoneg_done)
+    /// points to a cable problem. If
+    /// [`Phy::autoneg_expansion()`](phy/struct.Phy.html#method.autoneg_expansion)
+    /// never reports `page_received()` and not
+    /// `link_partner_autoneg_able()`, the partner does not
+    /// auto-negotiate and the link mode has to be forced.
+    pub fn autoneg_attempts(&self) -> u32 {
+        self.autoneg_attempts
+    }
+
+    /// Speed and duplex mode of the link, if the PHY reports them
+    fn negotiated_link(&mut self) -> Option<(Speed, Duplex)> {
+        let status = self.status();
+        self.link_up = status.link_detected();
+        if !status.link_detected() || !status.autoneg_done() {
+            return None;
+        }
+        let duplex = if status.is_full_duplex()? {
+            Duplex::Full
+        } else {
+            Duplex::Half
+        };
+        Some((status.speed()?, duplex))
+    }
+
+    /// Set the MAC's speed and duplex mode
+    fn set_speed_and_duplex(&mut self, speed: Speed, duplex: Duplex) {
+        self.config.speed = speed;
+        self.config.duplex = duplex;
+        let fast_ethernet = speed == Speed::HundredMbps;
+        let full_duplex = duplex == Duplex::Full;
+        self.eth_mac.maccr.modify(|_, w| {
+            w.fes()
+                .bit(fast_ethernet)
+                .dm()
+                .bit(full_duplex)
+                .rd()
+                .bit(full_duplex)
+        });
+    }
+
     /// Is Rx DMA currently running?
     ///
     /// It stops if the ring is full. Call `recv_next()` to free an
@@ -283,27 +828,612 @@ impl<'rx, 'tx> Eth<'rx, 'tx> {
         self.rx_ring.running_state(&self.eth_dma).is_running()
     }
 
-    /// Receive the next packet (if any is ready), or return `None`
-    /// immediately.
+    /// Number of received frames that are waiting for
+    /// [`recv_next()`](#method.recv_next)
+    ///
+    /// Counts the descriptors that the DMA engine has filled and
+    /// handed back, so a scheduler can decide whether to keep
+    /// draining or to sleep. Descriptors that will be reported as
+    /// an `RxError` are counted as well.
+    pub fn rx_pending(&self) -> usize {
+        self.rx_ring.pending()
+    }
+
+    /// State of the Rx DMA engine
+    ///
+    /// `RunningState::Suspended` means that the ring was full, see
+    /// [`resume_rx()`](#method.resume_rx).
+    pub fn rx_state(&self) -> RunningState {
+        self.rx_ring.running_state(&self.eth_dma)
+    }
+
+    /// State of the Tx DMA engine
+    ///
+    /// `RunningState::Suspended` means that there was nothing left to
+    /// send, or a transmit underflow occurred, see
+    /// [`resume_tx()`](#method.resume_tx).
+    pub fn tx_state(&self) -> RunningState {
+        self.tx_ring.running_state(&self.eth_dma)
+    }
+
+    /// Coalesce receive interrupts with the receive watchdog timer
+    ///
+    /// Instead of raising the receive interrupt for every frame, the
+    /// DMA engine starts the watchdog when a frame has been received
+    /// and raises it once the watchdog expires after `cycles` × 256
+    /// HCLK cycles. Drain all frames, e.g. with
+    /// [`for_each_frame()`](#method.for_each_frame), when
+    /// handling it.
+    ///
+    /// `0` disables coalescing.
+    pub fn set_rx_coalesce(&mut self, cycles: u8) {
+        self.rx_ring.set_interrupt_on_completion(cycles == 0);
+        self.eth_dma
+            .dmarswtr
+            .write(|w| unsafe { w.rswtc().bits(cycles) });
+    }
+
+    /// Index of the Rx ring entry that the DMA engine currently
+    /// points to, read from `dmachrdr`
+    ///
+    /// `None` if the DMA engine points outside of the ring, e.g.
+    /// before it was started.
+    pub fn rx_current_descriptor(&self) -> Option<usize> {
+        self.rx_ring.current_entry(&self.eth_dma)
+    }
+
+    /// Index of the Tx ring entry that the DMA engine currently
+    /// points to, read from `dmachtdr`
+    ///
+    /// `None` if the DMA engine points outside of the ring, e.g.
+    /// before it was started.
+    pub fn tx_current_descriptor(&self) -> Option<usize> {
+        self.tx_ring.current_entry(&self.eth_dma)
+    }
+
+    /// The words RDES0..RDES3 of the Rx descriptor at `index`
+    /// (feature: `debug-descriptors`)
+    ///
+    /// # Panics
+    ///
+    /// If `index` is outside of the Rx ring.
+    #[cfg(feature = "debug-descriptors")]
+    pub fn rx_descriptor(&self, index: usize) -> [u32; 4] {
+        self.rx_ring.descriptor_words(index)
+    }
+
+    /// The words TDES0..TDES3 of the Tx descriptor at `index`
+    /// (feature: `debug-descriptors`)
+    ///
+    /// # Panics
+    ///
+    /// If `index` is outside of the Tx ring.
+    #[cfg(feature = "debug-descriptors")]
+    pub fn tx_descriptor(&self, index: usize) -> [u32; 4] {
+        self.tx_ring.descriptor_words(index)
+    }
+
+    /// Demand that the Rx DMA polls the current `RxDescriptor` again
+    ///
+    /// The DMA suspends when it runs into a descriptor that is still
+    /// owned by software (ring full). Once entries have been freed,
+    /// this restarts reception. Dropping an `RxPacket` and
+    /// [`recv_next()`](#method.recv_next) do this automatically when
+    /// they see the suspended state.
+    pub fn resume_rx(&self) {
+        self.rx_ring.demand_poll(&self.eth_dma);
+    }
+
+    /// Receive the next packet into `dst`
+    ///
+    /// Copies the frame and hands the descriptor back to the DMA
+    /// engine before returning, so that it does not stay occupied
+    /// while the data is processed. Returns the length of the frame.
+    ///
+    /// A frame that does not fit into `dst` is dropped with
+    /// `Err(RxError::BufferTooSmall)`.
+    pub fn recv_next_into(&mut self, dst: &mut [u8]) -> Result<usize, RxError> {
+        let pkt = self.recv_next()?;
+        let len = pkt.len();
+        if len > dst.len() {
+            return Err(RxError::BufferTooSmall);
+        }
+        dst[..len].copy_from_slice(&pkt);
+        Ok(len)
+    }
+
+    /// Receive the next packet into a `heapless::Vec`
+    ///
+    /// Like [`recv_next_into()`](#method.recv_next_into), the
+    /// descriptor is handed back before returning. A frame that
+    /// does not fit into `N` bytes is dropped with
+    /// `Err(RxError::BufferTooSmall)`.
+    #[cfg(feature = "heapless")]
+    pub fn recv_next_vec<const N: usize>(&mut self) -> Result<heapless::Vec<u8, N>, RxError> {
+        let pkt = self.recv_next()?;
+        heapless::Vec::from_slice(&pkt).map_err(|()| RxError::BufferTooSmall)
+    }
+
+    /// Report receive timestamps through
+    /// `RxPacket::timestamp()`
+    ///
+    /// Timestamp capture itself must be enabled in the
+    /// `ETHERNET_PTP` peripheral (`ptptscr`), which this driver does
+    /// not own. The DMA engine then writes the time of arrival in
+    /// place of the descriptor's buffer addresses, which are
+    /// restored before the descriptor is handed back.
+    ///
+    /// The descriptor flags a valid timestamp with the bit that
+    /// otherwise reports IP header checksum errors, so
+    /// `RxPacket::ip_header_checksum()` is not reliable while
+    /// timestamps are enabled.
+    pub fn set_rx_timestamps(&mut self, enabled: bool) {
+        self.rx_ring.set_timestamps(enabled);
+    }
+
+    /// Capture the time of transmission of the frames sent from now
+    /// on, to be read with
+    /// [`take_tx_timestamp()`](#method.take_tx_timestamp)
+    ///
+    /// As with [`set_rx_timestamps()`](#method.set_rx_timestamps),
+    /// timestamping must be enabled in the `ETHERNET_PTP`
+    /// peripheral.
+    pub fn set_tx_timestamps(&mut self, enabled: bool) {
+        self.tx_ring.set_timestamps(enabled);
+    }
+
+    /// Take the oldest Tx timestamp that has not been taken yet
+    ///
+    /// Timestamps become available when a frame has been
+    /// transmitted, which is reported as `InterruptReason::tx`. Call
+    /// this until it returns `None` then, instead of polling for
+    /// every frame. A timestamp is lost once its descriptor is used
+    /// for sending again.
+    pub fn take_tx_timestamp(&mut self) -> Option<u64> {
+        self.tx_ring.take_timestamp()
+    }
+
+    /// Deliver frames that were received with errors (CRC error,
+    /// collision, watchdog timeout, overflow) to software?
+    ///
+    /// They are dropped by default. In
+    /// [`RxMode::CutThrough`](enum.RxMode.html#variant.CutThrough),
+    /// frames that are already being transferred cannot be dropped
+    /// anymore. Stays enabled in the MAC while
+    /// [`set_crc_error_delivery()`](#method.set_crc_error_delivery)
+    /// is.
+    pub fn set_forward_error_frames(&mut self, enabled: bool) {
+        self.config.forward_error_frames = enabled;
+        let fef = enabled || self.rx_ring.crc_errors();
+        self.eth_dma.dmaomr.modify(|_, w| w.fef().bit(fef));
+    }
+
+    /// Let the MAC drop frames that failed checksum offload
+    /// verification
+    ///
+    /// This is the default. When disabled, they are delivered, and
+    /// [`RxPacket::ip_header_checksum()`](struct.RxPacket.html#method.ip_header_checksum)
+    /// or
+    /// [`RxPacket::payload_checksum()`](struct.RxPacket.html#method.payload_checksum)
+    /// return `ChecksumResult::Invalid`. Has no effect without
+    /// checksum offload, or in
+    /// [`RxMode::CutThrough`](enum.RxMode.html#variant.CutThrough).
+    pub fn set_drop_checksum_error_frames(&mut self, enabled: bool) {
+        self.config.drop_checksum_error_frames = enabled;
+        self.eth_dma.dmaomr.modify(|_, w| w.dtcefd().bit(!enabled));
+    }
+
+    /// Deliver frames that failed the FCS check, flagged by
+    /// [`RxPacket::received_with_crc_error()`](struct.RxPacket.html#method.received_with_crc_error)
+    ///
+    /// For line monitoring, where such frames are counted or
+    /// inspected rather than dropped. Enabling this also makes the
+    /// MAC forward error frames, without which it drops them, and
+    /// disabling it restores the setting of
+    /// [`set_forward_error_frames()`](#method.set_forward_error_frames).
+    /// Frames with other errors are still reported as
+    /// `RxError::DmaError`.
+    pub fn set_crc_error_delivery(&mut self, enabled: bool) {
+        self.rx_ring.set_crc_errors(enabled);
+        let fef = enabled || self.config.forward_error_frames;
+        self.eth_dma.dmaomr.modify(|_, w| w.fef().bit(fef));
+    }
+
+    /// Deliver frames shorter than 64 bytes but without errors to
+    /// software?
+    ///
+    /// They are dropped by default.
+    pub fn set_forward_undersized_good_frames(&mut self, enabled: bool) {
+        self.config.forward_undersized_good_frames = enabled;
+        self.eth_dma.dmaomr.modify(|_, w| w.fugf().bit(enabled));
+    }
+
+    /// Receive the next packet (if any is ready), or return
+    /// `Err(RxError::WouldBlock)` immediately.
+    ///
+    /// Each descriptor is handed out once: the ring only advances
+    /// past it after the DMA engine has released it. Because this
+    /// takes `&mut self`, draining from both a poll loop and an
+    /// interrupt handler requires sharing the `Eth` through a
+    /// critical section, e.g. a `cortex_m::interrupt::Mutex`, which
+    /// serializes the two.
+    ///
+    /// The descriptor stays with software until the `RxPacket` is
+    /// dropped or [freed](struct.RxPacket.html#method.free). To keep
+    /// as many descriptors armed as possible during bursts, use
+    /// [`recv_with()`](#method.recv_with) instead.
     pub fn recv_next(&mut self) -> Result<RxPacket, RxError> {
-        self.rx_ring.recv_next(&self.eth_dma)
+        let pkt = self.rx_ring.recv_next(&self.eth_dma)?;
+        #[cfg(feature = "capture")]
+        self.capture.record(&pkt);
+        Ok(pkt)
+    }
+
+    /// Receive the next packet and pass it to `f`
+    ///
+    /// The descriptor is handed back to the DMA engine as soon as `f`
+    /// returns, and reception is resumed if the DMA had suspended on
+    /// a full ring. Returns the result of `f`, or
+    /// `Err(RxError::WouldBlock)` if no packet is ready.
+    ///
+    /// ```ignore
+    /// let len = eth.recv_with(|pkt| {
+    ///     // handle received pkt
+    ///     pkt.len()
+    /// })?;
+    /// ```
+    pub fn recv_with<R, F: FnOnce(&RxPacket) -> R>(&mut self, f: F) -> Result<R, RxError> {
+        let pkt = self.recv_next()?;
+        let result = f(&pkt);
+        pkt.free();
+        self.rx_ring.demand_poll(&self.eth_dma);
+        Ok(result)
+    }
+
+    /// Like [`recv_next()`](#method.recv_next), but returns
+    /// `Ok(None)` if no packet is ready, so that `Err` only
+    /// stands for frames that were received with errors.
+    ///
+    /// ```ignore
+    /// while let Some(pkt) = eth.try_recv_next()? {
+    ///     // handle received pkt
+    /// }
+    /// ```
+    pub fn try_recv_next(&mut self) -> Result<Option<RxPacket>, RxError> {
+        match self.recv_next() {
+            Ok(pkt) => Ok(Some(pkt)),
+            Err(RxError::WouldBlock) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Pass all packets that are ready to be received to `f`, and
+    /// return how many there were
+    ///
+    /// Stops once the next ring entry is still owned by the DMA
+    /// engine (where `recv_next()` would return
+    /// `Err(RxError::WouldBlock)`), and after one pass through the
+    /// ring at most. Other errors are passed on. Each packet is
+    /// freed when `f` returns, so only one is held at a time.
+    ///
+    /// ```ignore
+    /// eth.for_each_frame(|pkt| {
+    ///     if let Ok(pkt) = pkt {
+    ///         // handle received pkt
+    ///     }
+    /// });
+    /// ```
+    pub fn for_each_frame<F: FnMut(Result<RxPacket, RxError>)>(&mut self, mut f: F) -> usize {
+        let mut frames = self.rx_frames();
+        let mut count = 0;
+        while let Some(result) = frames.next_frame() {
+            f(result);
+            count += 1;
+        }
+        count
+    }
+
+    /// Drain the packets that are ready to be received, one at a
+    /// time
+    ///
+    /// Stops under the same conditions as
+    /// [`for_each_frame()`](#method.for_each_frame). This is not an
+    /// `Iterator`: each packet borrows the `RxFrames`, so it has to
+    /// be dropped before the next one is taken, and two packets can
+    /// never alias the same ring entry.
+    ///
+    /// ```ignore
+    /// let mut frames = eth.rx_frames();
+    /// while let Some(pkt) = frames.next_frame() {
+    ///     // handle received pkt
+    /// }
+    /// ```
+    pub fn rx_frames(&mut self) -> RxFrames<'_, 'rx, 'tx> {
+        let remaining = self.rx_ring.entries_len();
+        RxFrames {
+            eth: self,
+            remaining,
+        }
     }
 
     /// Is Tx DMA currently running?
+    ///
+    /// The Tx DMA suspends when it has sent all queued frames, and
+    /// also after a transmit underflow. A suspended DMA does not
+    /// pick up new descriptors on its own: `send()` issues a poll
+    /// demand after each frame, and [`resume_tx()`](#method.resume_tx)
+    /// can be used to restart it if `tx_is_running()` stays `false`
+    /// while frames are queued.
     pub fn tx_is_running(&self) -> bool {
         self.tx_ring.is_running(&self.eth_dma)
     }
 
+    /// Recover the Tx DMA from a suspended state
+    ///
+    /// Clears a pending transmit underflow and demands that the DMA
+    /// polls the current `TxDescriptor` again.
+    /// [`eth_interrupt_handler()`](fn.eth_interrupt_handler.html)
+    /// does this automatically when it sees an underflow.
+    pub fn resume_tx(&self) {
+        if self.eth_dma.dmasr.read().tus().bit_is_set() {
+            self.eth_dma.dmasr.write(|w| w.tus().set_bit());
+        }
+        self.tx_ring.demand_poll(&self.eth_dma);
+    }
+
+    /// Number of Tx descriptors that the DMA engine has finished
+    /// since the last call
+    ///
+    /// Descriptors complete in the order they were sent. A frame
+    /// sent with [`send_segments()`](#method.send_segments) counts
+    /// once per non-empty segment. At most one ring's worth of
+    /// descriptors is remembered: when a descriptor is reused before
+    /// it has been counted, its completion is dropped.
+    ///
+    /// A descriptor that the hardware reports as failed, e.g. with
+    /// `TxError::LateCollision`, is returned as `Err` on its own, and
+    /// counts as completed. Counting stops before it, so call this
+    /// until it returns `Ok(0)` to catch up:
+    ///
+    /// ```ignore
+    /// loop {
+    ///     match eth.poll_tx_complete() {
+    ///         Ok(0) => break,
+    ///         Ok(_) => {}
+    ///         Err(TxError::NoCarrier) => link_lost(),
+    ///         Err(_) => errors += 1,
+    ///     }
+    /// }
+    /// ```
+    pub fn poll_tx_complete(&mut self) -> Result<usize, TxError> {
+        self.tx_ring.poll_completed()
+    }
+
+    /// Iterate over the Tx descriptors that the DMA engine has
+    /// finished, with their ring index and transmit status
+    ///
+    /// This shares its bookkeeping with
+    /// [`poll_tx_complete()`](#method.poll_tx_complete): each
+    /// descriptor is reported by only one of them, and at most once
+    /// per frame it sent. Drain at least once per ring length of
+    /// frames: a descriptor that is reused before it has been
+    /// reported is not reported for its earlier frame. The index
+    /// identifies the frame, e.g. to release a buffer that was sent
+    /// with [`send_buffer_ptr()`](#method.send_buffer_ptr):
+    ///
+    /// ```ignore
+    /// for (index, status) in eth.drain_tx_completions() {
+    ///     pool.release(index);
+    ///     if status.is_err() {
+    ///         errors += 1;
+    ///     }
+    /// }
+    /// ```
+    pub fn drain_tx_completions(&mut self) -> TxCompletions<'_, 'tx> {
+        self.tx_ring.completions()
+    }
+
+    /// Largest untagged frame without FCS, as smoltcp expects it
+    ///
+    /// This is 1514 bytes, an IP MTU of 1500. The ring buffers are
+    /// larger, leaving room for a VLAN tag and the FCS.
+    pub fn mtu(&self) -> usize {
+        MAX_FRAME_LEN
+    }
+
+    /// Number of Tx descriptors that are currently free
+    ///
+    /// This many packets can be passed to [`send()`](#method.send)
+    /// before it returns `Err(TxError::WouldBlock)`.
+    pub fn tx_available(&self) -> usize {
+        self.tx_ring.available()
+    }
+
     /// Send a packet
+    ///
+    /// Checksums are inserted by the MAC if checksum offload is
+    /// enabled, see
+    /// [`set_checksum_offload()`](#method.set_checksum_offload).
     pub fn send<F: FnOnce(&mut [u8]) -> R, R>(
         &mut self,
         length: usize,
         f: F,
     ) -> Result<R, TxError> {
+        let (length, f) = self.stamp_source_address(length, f)?;
         let result = self.tx_ring.send(length, f);
         self.tx_ring.demand_poll(&self.eth_dma);
         result
     }
+
+    /// Send a packet, choosing which checksums the MAC inserts
+    /// into this frame
+    ///
+    /// Pass `TxChecksum::None` for frames that are already
+    /// checksummed or are not IP at all, so that they go out
+    /// unmodified, and `TxChecksum::IpHeader` for UDP datagrams that
+    /// deliberately carry no checksum.
+    pub fn send_with_checksum<F: FnOnce(&mut [u8]) -> R, R>(
+        &mut self,
+        length: usize,
+        checksum_insertion: TxChecksum,
+        f: F,
+    ) -> Result<R, TxError> {
+        let (length, f) = self.stamp_source_address(length, f)?;
+        let result = self
+            .tx_ring
+            .send_with_checksum(length, checksum_insertion, f);
+        self.tx_ring.demand_poll(&self.eth_dma);
+        result
+    }
+
+    /// Enable or disable checksum offload
+    ///
+    /// This controls checksum verification of received frames and
+    /// the default for checksum insertion in
+    /// [`send()`](#method.send).
+    pub fn set_checksum_offload(&mut self, enabled: bool) {
+        self.config.checksum_offload = enabled;
+        self.eth_mac.maccr.modify(|_, w| w.ipco().bit(enabled));
+        self.rx_ring.set_checksum_offload(enabled);
+        self.tx_ring.set_checksum_insertion(enabled);
+    }
+
+    /// Verify checksums in software where the MAC has not
+    ///
+    /// When enabled, the IPv4 header and TCP/UDP/ICMP checksums
+    /// that [`RxPacket`](struct.RxPacket.html) would report as
+    /// `ChecksumResult::NotChecked` are computed on receive, while
+    /// the results of the hardware are kept. This covers e.g.
+    /// 802.1Q tagged frames, and all frames while checksum offload
+    /// is disabled. Fragmented frames and IPv6 extension headers
+    /// stay `NotChecked`.
+    pub fn set_software_checksum_fallback(&mut self, enabled: bool) {
+        self.rx_ring.set_software_checksums(enabled);
+    }
+
+    /// Send a packet by copying `data`
+    pub fn send_slice(&mut self, data: &[u8]) -> Result<(), TxError> {
+        if data.len() > MAX_FRAME_LEN {
+            return Err(TxError::FrameTooLarge);
+        }
+        self.send(data.len(), |buf| buf.copy_from_slice(data))
+    }
+
+    /// Borrow the buffer of the next free Tx entry, or `None` if the
+    /// DMA engine still owns it
+    ///
+    /// The frame is written straight into the ring, and sent by
+    /// [`TxSlot::send()`](struct.TxSlot.html#method.send):
+    ///
+    /// ```ignore
+    /// if let Some(mut slot) = eth.acquire_tx() {
+    ///     slot[..frame.len()].copy_from_slice(&frame);
+    ///     slot.send(frame.len())?;
+    /// }
+    /// ```
+    ///
+    /// Unlike [`send()`](#method.send), the source address mode
+    /// (see [`set_source_address_mode()`](#method.set_source_address_mode))
+    /// is not applied.
+    pub fn acquire_tx(&mut self) -> Option<TxSlot<'_, 'tx>> {
+        self.tx_ring.acquire(&self.eth_dma)
+    }
+
+    /// Send the frame at `ptr` directly, instead of copying it into
+    /// a Tx buffer
+    ///
+    /// Fails with `TxError::FrameTooLarge` if `len` exceeds
+    /// [`mtu()`](#method.mtu).
+    ///
+    /// # Safety
+    ///
+    /// The DMA engine reads the frame at some point after this
+    /// function has returned. Until
+    /// [`poll_tx_complete()`](#method.poll_tx_complete) has counted
+    /// this descriptor as completed, or the Tx DMA has been
+    /// [stopped](#method.stop), the caller must ensure that:
+    ///
+    /// * the `len` bytes at `ptr` stay valid and are not modified,
+    ///   nor moved or freed, and
+    /// * the buffer is in memory that the DMA engine can access,
+    ///   which excludes Core-Coupled Memory (CCM) on STM32F4xx.
+    pub unsafe fn send_buffer_ptr(&mut self, ptr: *const u8, len: usize) -> Result<(), TxError> {
+        if len > MAX_FRAME_LEN {
+            return Err(TxError::FrameTooLarge);
+        }
+        let result = self.tx_ring.send_buffer_ptr(ptr, len);
+        self.tx_ring.demand_poll(&self.eth_dma);
+        result
+    }
+
+    /// Send one frame that is made up of `segments`, e.g. a header
+    /// and a payload
+    ///
+    /// Each non-empty segment is copied into a Tx descriptor of its
+    /// own, and the DMA engine gathers them, so they do not need to
+    /// be joined first. Fails with `TxError::FrameTooLarge` if the
+    /// total length exceeds [`mtu()`](#method.mtu) or there are more
+    /// segments than Tx descriptors, and with `TxError::WouldBlock` if not enough
+    /// consecutive descriptors are free.
+    pub fn send_segments(&mut self, segments: &[&[u8]]) -> Result<(), TxError> {
+        let length: usize = segments.iter().map(|segment| segment.len()).sum();
+        if length > MAX_FRAME_LEN {
+            return Err(TxError::FrameTooLarge);
+        }
+        let result = self.tx_ring.send_segments(segments);
+        self.tx_ring.demand_poll(&self.eth_dma);
+        result
+    }
+}
+
+/// Draining receiver, returned by
+/// [`Eth::rx_frames()`](struct.Eth.html#method.rx_frames)
+pub struct RxFrames<'a, 'rx, 'tx> {
+    eth: &'a mut Eth<'rx, 'tx>,
+    /// Packets left in this pass through the ring
+    remaining: usize,
+}
+
+impl<'a, 'rx, 'tx> RxFrames<'a, 'rx, 'tx> {
+    /// Receive the next packet, or `None` once the ring is drained
+    pub fn next_frame(&mut self) -> Option<Result<RxPacket<'_>, RxError>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        match self.eth.recv_next() {
+            Err(RxError::WouldBlock) => None,
+            result => {
+                self.remaining -= 1;
+                Some(result)
+            }
+        }
+    }
+}
+
+/// Status flags that were pending when
+/// [`eth_interrupt_handler()`](fn.eth_interrupt_handler.html) ran
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct InterruptReason {
+    /// A frame has been received
+    pub rx: bool,
+    /// A frame has been transmitted. Its timestamp, if enabled, can
+    /// be taken with
+    /// [`take_tx_timestamp()`](struct.Eth.html#method.take_tx_timestamp).
+    pub tx: bool,
+    /// The Rx DMA found no free descriptor and suspended. It
+    /// resumes as soon as an `RxPacket` is dropped or freed.
+    pub rx_buffer_unavailable: bool,
+    /// The Tx DMA suspended because of a transmit buffer underflow.
+    /// It has been asked to poll again.
+    pub tx_underflow: bool,
+    /// The first buffer of a frame has been filled, see
+    /// [`set_early_rx_interrupt()`](struct.Eth.html#method.set_early_rx_interrupt)
+    pub early_rx: bool,
+    /// The PTP module raised its time stamp trigger interrupt
+    ///
+    /// This is not cleared here but by reading `ptptssr` of the
+    /// `ETHERNET_PTP` peripheral, which this driver does not own.
+    pub timestamp_trigger: bool,
 }
 
 /// Call in interrupt handler to clear interrupt reason, when
@@ -314,9 +1444,47 @@ impl<'rx, 'tx> Eth<'rx, 'tx> {
 /// * Via the [`Eth`](struct.Eth.html) driver instance that your interrupt handler has access to.
 /// * By unsafely getting `Peripherals`.
 ///
-/// TODO: could return interrupt reason
-pub fn eth_interrupt_handler(eth_dma: &ETHERNET_DMA) {
-    eth_dma
-        .dmasr
-        .write(|w| w.nis().set_bit().rs().set_bit().ts().set_bit());
+/// A Tx DMA that suspended on a transmit underflow is asked to poll
+/// again. An Rx DMA that suspended because the ring was full is only
+/// reported: it cannot resume before software hands back a
+/// descriptor, and dropping or freeing an `RxPacket` resumes it.
+///
+/// With the `async` feature, this also wakes the tasks waiting in
+/// [`recv_async()`](struct.Eth.html#method.recv_async) and
+/// [`send_async()`](struct.Eth.html#method.send_async).
+pub fn eth_interrupt_handler(eth_dma: &ETHERNET_DMA) -> InterruptReason {
+    let status = eth_dma.dmasr.read();
+    let reason = InterruptReason {
+        rx: status.rs().bit_is_set(),
+        tx: status.ts().bit_is_set(),
+        rx_buffer_unavailable: status.rbus().bit_is_set(),
+        tx_underflow: status.tus().bit_is_set(),
+        early_rx: status.ers().bit_is_set(),
+        timestamp_trigger: (status.bits() & DMASR_TSTS) != 0,
+    };
+
+    eth_dma.dmasr.write(|w| {
+        w.nis()
+            .set_bit()
+            .rs()
+            .set_bit()
+            .ts()
+            .set_bit()
+            .rbus()
+            .set_bit()
+            .tus()
+            .set_bit()
+            .ers()
+            .set_bit()
+    });
+
+    if reason.tx_underflow {
+        // Transmit poll demand
+        eth_dma.dmatpdr.write(|w| w.tpd().poll());
+    }
+
+    #[cfg(feature = "async")]
+    asynch::on_interrupt(&reason);
+
+    reason
 }
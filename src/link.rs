@@ -0,0 +1,110 @@
+use crate::phy::{Phy, PhyStatus, Speed};
+use crate::Eth;
+
+/// Change of the link state, reported by
+/// [`LinkMonitor::poll()`](struct.LinkMonitor.html#method.poll)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkEvent {
+    /// Link has come up, or its speed or duplex mode has changed
+    Up {
+        speed: Option<Speed>,
+        full_duplex: Option<bool>,
+    },
+    /// Link has been lost
+    Down,
+}
+
+/// Remembers the last `PhyStatus` to detect link changes
+///
+/// Call [`poll()`](#method.poll) periodically, and reconfigure the
+/// MAC when it reports a new link:
+///
+/// ```ignore
+/// let mut monitor = LinkMonitor::new();
+/// loop {
+///     if let Some(LinkEvent::Up { .. }) = monitor.poll(&eth.get_phy()) {
+///         // ...
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct LinkMonitor {
+    last: Option<PhyStatus>,
+}
+
+impl LinkMonitor {
+    /// Start with the link considered down
+    pub fn new() -> Self {
+        LinkMonitor { last: None }
+    }
+
+    /// Read the PHY status and return an event if link, speed, or
+    /// duplex mode differ from the previous call
+    pub fn poll(&mut self, phy: &Phy) -> Option<LinkEvent> {
+        let status = phy.status();
+        let changed = match self.last {
+            None => status.link_detected(),
+            Some(last) => last != status,
+        };
+        self.last = Some(status);
+
+        if !changed {
+            None
+        } else if status.link_detected() {
+            Some(LinkEvent::Up {
+                speed: status.speed(),
+                full_duplex: status.is_full_duplex(),
+            })
+        } else {
+            Some(LinkEvent::Down)
+        }
+    }
+
+    /// Status seen by the last call to [`poll()`](#method.poll)
+    pub fn last_status(&self) -> Option<PhyStatus> {
+        self.last
+    }
+}
+
+impl Default for LinkMonitor {
+    fn default() -> Self {
+        LinkMonitor::new()
+    }
+}
+
+impl<'rx, 'tx> Eth<'rx, 'tx> {
+    /// Read the PHY status and report a change of link, speed, or
+    /// duplex mode since the previous call
+    ///
+    /// The smoltcp `Device` trait has no way to signal a
+    /// renegotiated link, so call this periodically next to
+    /// `iface.poll()`. On `LinkEvent::Up`, reconfigure the MAC and
+    /// adjust any rate-dependent timers:
+    ///
+    /// ```ignore
+    /// if let Some(LinkEvent::Up { .. }) = iface.device_mut().poll_link() {
+    ///     iface.device_mut().sync_mac_to_phy();
+    ///     // ...
+    /// }
+    /// ```
+    ///
+    /// Also updates [`is_link_up()`](#method.is_link_up). Never
+    /// reports an event with
+    /// [`Config::fixed_link`](struct.Config.html#structfield.fixed_link).
+    pub fn poll_link(&mut self) -> Option<LinkEvent> {
+        if self.config.fixed_link {
+            return None;
+        }
+        let phy = Phy::new(
+            &self.eth_mac.macmiiar,
+            &self.eth_mac.macmiidr,
+            self.phy_addr,
+        );
+        let event = self.link_monitor.poll(&phy);
+        self.link_up = self
+            .link_monitor
+            .last_status()
+            .map_or(false, |status| status.link_detected());
+        event
+    }
+}
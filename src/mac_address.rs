@@ -0,0 +1,157 @@
+//! Typed MAC addresses for the station address and the filters
+
+use core::fmt;
+use core::str::FromStr;
+
+/// An Ethernet MAC address
+///
+/// Converts from `[u8; 6]` and `&[u8; 6]`, so plain arrays can still
+/// be passed to the methods that take an `impl Into<MacAddress>`.
+/// Parses from and formats as `aa:bb:cc:dd:ee:ff`:
+///
+/// ```ignore
+/// const MAC: MacAddress = MacAddress::new(0x02, 0x00, 0xDE, 0xAD, 0xBE, 0xEF);
+/// let gateway: MacAddress = "00:11:22:33:44:55".parse()?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MacAddress([u8; 6]);
+
+impl MacAddress {
+    /// `ff:ff:ff:ff:ff:ff`
+    pub const BROADCAST: MacAddress = MacAddress([0xFF; 6]);
+
+    /// Address made of the six bytes in transmission order
+    pub const fn new(a0: u8, a1: u8, a2: u8, a3: u8, a4: u8, a5: u8) -> Self {
+        MacAddress([a0, a1, a2, a3, a4, a5])
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; 6] {
+        &self.0
+    }
+
+    pub fn is_broadcast(&self) -> bool {
+        *self == MacAddress::BROADCAST
+    }
+
+    /// Is the group bit set? Includes the broadcast address.
+    pub fn is_multicast(&self) -> bool {
+        self.0[0] & 1 == 1
+    }
+
+    pub fn is_unicast(&self) -> bool {
+        !self.is_multicast()
+    }
+}
+
+impl From<[u8; 6]> for MacAddress {
+    fn from(bytes: [u8; 6]) -> Self {
+        MacAddress(bytes)
+    }
+}
+
+impl From<&[u8; 6]> for MacAddress {
+    fn from(bytes: &[u8; 6]) -> Self {
+        MacAddress(*bytes)
+    }
+}
+
+impl From<MacAddress> for [u8; 6] {
+    fn from(addr: MacAddress) -> Self {
+        addr.0
+    }
+}
+
+impl fmt::Display for MacAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let [a0, a1, a2, a3, a4, a5] = self.0;
+        write!(
+            f,
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            a0, a1, a2, a3, a4, a5
+        )
+    }
+}
+
+/// A string is not six hexadecimal bytes separated by `:` or `-`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseMacAddressError;
+
+impl FromStr for MacAddress {
+    type Err = ParseMacAddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let separator = if s.contains('-') { '-' } else { ':' };
+        let mut bytes = [0; 6];
+        let mut parts = s.split(separator);
+        for byte in bytes.iter_mut() {
+            let part = parts.next().ok_or(ParseMacAddressError)?;
+            if part.len() != 2 || !part.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(ParseMacAddressError);
+            }
+            *byte = u8::from_str_radix(part, 16).map_err(|_| ParseMacAddressError)?;
+        }
+        if parts.next().is_some() {
+            return Err(ParseMacAddressError);
+        }
+        Ok(MacAddress(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::{MacAddress, ParseMacAddressError};
+    use std::string::ToString;
+
+    const ADDR: MacAddress = MacAddress::new(0x02, 0x00, 0xDE, 0xAD, 0xBE, 0xEF);
+
+    #[test]
+    fn parses_either_separator_and_case() {
+        for &s in [
+            "02:00:de:ad:be:ef",
+            "02:00:DE:AD:BE:EF",
+            "02-00-de-ad-be-ef",
+            "02-00-De-aD-BE-ef",
+        ]
+        .iter()
+        {
+            assert_eq!(s.parse(), Ok(ADDR), "parsing {:?}", s);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_addresses() {
+        for &s in [
+            "",
+            "02:00-de:ad:be:ef",
+            "02-00:de-ad-be-ef",
+            "02:00:de:ad:be",
+            "02:00:de:ad:be:ef:01",
+            "02:00:de:ad:be:",
+            "2:00:de:ad:be:ef",
+            "02:00:de:ad:be:f",
+            "002:00:de:ad:be:ef",
+            "+2:00:de:ad:be:ef",
+            "-2:00:de:ad:be:ef",
+            "02:00:de:ad:be:eg",
+            "02 00 de ad be ef",
+        ]
+        .iter()
+        {
+            assert_eq!(
+                s.parse::<MacAddress>(),
+                Err(ParseMacAddressError),
+                "parsing {:?}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let s = ADDR.to_string();
+        assert_eq!(s, "02:00:de:ad:be:ef");
+        assert_eq!(s.parse(), Ok(ADDR));
+    }
+}
@@ -0,0 +1,167 @@
+//! MAC debug register, only available on STM32F4xx
+
+use crate::Eth;
+
+const MACDBGR_MMRPEA: u32 = 1 << 0;
+const MACDBGR_RFWRA: u32 = 1 << 4;
+const MACDBGR_RFRCS_SHIFT: u32 = 5;
+const MACDBGR_RFFL_SHIFT: u32 = 8;
+const MACDBGR_MMTEA: u32 = 1 << 16;
+const MACDBGR_MTFCS_SHIFT: u32 = 17;
+const MACDBGR_MTP: u32 = 1 << 19;
+const MACDBGR_TFRS_SHIFT: u32 = 20;
+const MACDBGR_TFWA: u32 = 1 << 22;
+const MACDBGR_TFNE: u32 = 1 << 24;
+const MACDBGR_TFF: u32 = 1 << 25;
+
+/// Fill level of the Rx FIFO
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RxFifoLevel {
+    Empty,
+    /// Below the flow control deactivate threshold
+    BelowThreshold,
+    /// Above the flow control activate threshold
+    AboveThreshold,
+    Full,
+}
+
+/// What the Rx FIFO read controller is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RxFifoReadState {
+    Idle,
+    ReadingData,
+    ReadingStatus,
+    Flushing,
+}
+
+/// What the MAC transmit frame controller is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxFrameState {
+    Idle,
+    /// Waiting for the status of the previous frame, or for the
+    /// interframe gap or backoff period
+    Waiting,
+    /// Generating and transmitting a pause frame
+    SendingPause,
+    /// Transferring a frame to the MII
+    Transmitting,
+}
+
+/// What the Tx FIFO read controller is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxFifoReadState {
+    Idle,
+    Reading,
+    WaitingForStatus,
+    /// Writing the received status, or flushing the FIFO
+    WritingStatus,
+}
+
+/// Snapshot of the `macdbgr` register
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MacDebugStatus {
+    bits: u32,
+}
+
+impl MacDebugStatus {
+    fn field(&self, shift: u32) -> u32 {
+        (self.bits >> shift) & 0b11
+    }
+
+    /// Raw register value
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// MII receive protocol engine is receiving a frame?
+    pub fn rx_engine_active(&self) -> bool {
+        (self.bits & MACDBGR_MMRPEA) == MACDBGR_MMRPEA
+    }
+
+    /// Rx FIFO write controller is transferring a frame?
+    pub fn rx_fifo_write_active(&self) -> bool {
+        (self.bits & MACDBGR_RFWRA) == MACDBGR_RFWRA
+    }
+
+    pub fn rx_fifo_read_state(&self) -> RxFifoReadState {
+        match self.field(MACDBGR_RFRCS_SHIFT) {
+            0b00 => RxFifoReadState::Idle,
+            0b01 => RxFifoReadState::ReadingData,
+            0b10 => RxFifoReadState::ReadingStatus,
+            _ => RxFifoReadState::Flushing,
+        }
+    }
+
+    pub fn rx_fifo_level(&self) -> RxFifoLevel {
+        match self.field(MACDBGR_RFFL_SHIFT) {
+            0b00 => RxFifoLevel::Empty,
+            0b01 => RxFifoLevel::BelowThreshold,
+            0b10 => RxFifoLevel::AboveThreshold,
+            _ => RxFifoLevel::Full,
+        }
+    }
+
+    /// MII transmit engine is transmitting?
+    pub fn tx_engine_active(&self) -> bool {
+        (self.bits & MACDBGR_MMTEA) == MACDBGR_MMTEA
+    }
+
+    pub fn tx_frame_state(&self) -> TxFrameState {
+        match self.field(MACDBGR_MTFCS_SHIFT) {
+            0b00 => TxFrameState::Idle,
+            0b01 => TxFrameState::Waiting,
+            0b10 => TxFrameState::SendingPause,
+            _ => TxFrameState::Transmitting,
+        }
+    }
+
+    /// Transmitter is paused by a received pause frame?
+    pub fn tx_paused(&self) -> bool {
+        (self.bits & MACDBGR_MTP) == MACDBGR_MTP
+    }
+
+    pub fn tx_fifo_read_state(&self) -> TxFifoReadState {
+        match self.field(MACDBGR_TFRS_SHIFT) {
+            0b00 => TxFifoReadState::Idle,
+            0b01 => TxFifoReadState::Reading,
+            0b10 => TxFifoReadState::WaitingForStatus,
+            _ => TxFifoReadState::WritingStatus,
+        }
+    }
+
+    /// Tx FIFO write controller is transferring data?
+    pub fn tx_fifo_write_active(&self) -> bool {
+        (self.bits & MACDBGR_TFWA) == MACDBGR_TFWA
+    }
+
+    pub fn tx_fifo_not_empty(&self) -> bool {
+        (self.bits & MACDBGR_TFNE) == MACDBGR_TFNE
+    }
+
+    pub fn tx_fifo_full(&self) -> bool {
+        (self.bits & MACDBGR_TFF) == MACDBGR_TFF
+    }
+}
+
+impl<'rx, 'tx> Eth<'rx, 'tx> {
+    /// Read the state of the MAC's FIFOs and controllers
+    ///
+    /// Useful together with
+    /// [`rx_current_descriptor()`](#method.rx_current_descriptor) and
+    /// [`tx_current_descriptor()`](#method.tx_current_descriptor)
+    /// when the DMA engines stall.
+    pub fn mac_debug(&self) -> MacDebugStatus {
+        MacDebugStatus {
+            bits: self.eth_mac.macdbgr.read().bits(),
+        }
+    }
+
+    /// Current fill level of the Rx FIFO
+    ///
+    /// Sample this under load, along with the missed frame counters
+    /// in [`diagnostics()`](#method.diagnostics), to tune the flow
+    /// control thresholds.
+    pub fn rx_fifo_level(&self) -> RxFifoLevel {
+        self.mac_debug().rx_fifo_level()
+    }
+}
@@ -0,0 +1,298 @@
+//! Registers and bits defined by IEEE 802.3 clause 22, shared by all
+//! PHYs.
+
+#[allow(dead_code)]
+pub mod consts {
+    pub const PHY_REG_BMCR: u8 = 0x00; // Basic mode control register
+    pub const PHY_REG_BMSR: u8 = 0x01; // Basic mode status register
+    pub const PHY_REG_PHYIDR1: u8 = 0x02; // PHY Identifier Register #1
+    pub const PHY_REG_PHYIDR2: u8 = 0x03; // PHY Identifier Register #2
+    pub const PHY_REG_ANAR: u8 = 0x04; // Auto-Negotiation Advertisement Register
+    pub const PHY_REG_ANLPAR: u8 = 0x05; // Auto-Negotiation Link Partner Ability Register
+    pub const PHY_REG_ANER: u8 = 0x06; // Auto-Negotiation Expansion Register
+    pub const PHY_REG_ANNPTR: u8 = 0x07; // Auto-Negotiation Next Page TX
+    pub const PHY_REG_ANNPRR: u8 = 0x08; // Auto-Negotiation Next Page RX
+    pub const PHY_REG_MMDCTRL: u8 = 0x0D; // MMD Access Control Register
+    pub const PHY_REG_MMDAD: u8 = 0x0E; // MMD Access Address/Data Register
+
+    pub const PHY_REG_BMCR_RESET: u16 = 1 << 15; // 1 = reset, self-clearing upon completed reset
+    pub const PHY_REG_BMCR_LOOPBACK: u16 = 1 << 14;
+    pub const PHY_REG_BMCR_SPEED_SELECTION: u16 = 1 << 13; // 1 = 100Mb/s, 0 = 10 Mb/s
+    pub const PHY_REG_BMCR_AUTO_NEGOTIATION: u16 = 1 << 12; // 1 = on, disables speed and duplex selection
+    pub const PHY_REG_BMCR_POWER_DOWN: u16 = 1 << 11; // 1 = power down
+    pub const PHY_REG_BMCR_ISOLATE: u16 = 1 << 10; // 1 = isolate power from MII
+    pub const PHY_REG_BMCR_RESTART_AUTONEG: u16 = 1 << 9; // 1 = restart auto-negotiation, self-clearing
+    pub const PHY_REG_BMCR_DUPLEX_MODE: u16 = 1 << 8; // 1 = full duplex
+    pub const PHY_REG_BMCR_COLLISION_TEST: u16 = 1 << 7; // 1 = collision test enabled
+
+    pub const PHY_REG_BMSR_100_FULL: u16 = 1 << 14; // 1 = Device able to perform 100BASE-TX in full duplex mode
+    pub const PHY_REG_BMSR_100_HALF: u16 = 1 << 13; // 1 = Device able to perform 100BASE-TX in half duplex mode
+    pub const PHY_REG_BMSR_10_FULL: u16 = 1 << 12; // 1 = Device able to perform 10BASE-T in full duplex mode
+    pub const PHY_REG_BMSR_10_HALF: u16 = 1 << 11; // 1 = Device able to perform 10BASE-T in half duplex mode
+    pub const PHY_REG_BMSR_AUTONEG_COMPLETE: u16 = 1 << 5; // 1 = complete
+    pub const PHY_REG_BMSR_REMOTE_FAULT: u16 = 1 << 4; // 1 = Remote Fault condition detected
+    pub const PHY_REG_BMSR_LINK_STATUS: u16 = 1 << 2; // 1 = valid link
+    pub const PHY_REG_BMSR_JABBER_DETECT: u16 = 1 << 1; // 1 = jabber condition detected
+
+    // Technology ability field, shared by ANAR and ANLPAR
+    pub const PHY_REG_AN_10_HALF: u16 = 1 << 5; // 10BASE-T half duplex
+    pub const PHY_REG_AN_10_FULL: u16 = 1 << 6; // 10BASE-T full duplex
+    pub const PHY_REG_AN_100_HALF: u16 = 1 << 7; // 100BASE-TX half duplex
+    pub const PHY_REG_AN_100_FULL: u16 = 1 << 8; // 100BASE-TX full duplex
+    pub const PHY_REG_AN_100_T4: u16 = 1 << 9; // 100BASE-T4
+    pub const PHY_REG_AN_PAUSE: u16 = 1 << 10; // Symmetric pause
+    pub const PHY_REG_AN_ASYM_PAUSE: u16 = 1 << 11; // Asymmetric pause
+    pub const PHY_REG_AN_REMOTE_FAULT: u16 = 1 << 13;
+    pub const PHY_REG_AN_NEXT_PAGE: u16 = 1 << 15;
+    pub const PHY_REG_AN_SELECTOR_802_3: u16 = 0b00001; // IEEE 802.3
+
+    pub const PHY_REG_MMDCTRL_ADDRESS: u16 = 0b00 << 14; // MMDAD holds the register address
+    pub const PHY_REG_MMDCTRL_DATA: u16 = 0b01 << 14; // MMDAD holds the data, no post increment
+    pub const PHY_REG_MMDCTRL_DEVAD: u16 = 0x1F; // MMD device address
+
+    pub const PHY_REG_ANER_LP_AN_ABLE: u16 = 1 << 0; // 1 = link partner supports auto-negotiation
+    pub const PHY_REG_ANER_PAGE_RX: u16 = 1 << 1; // 1 = link code word received, latching
+    pub const PHY_REG_ANER_NP_ABLE: u16 = 1 << 2; // 1 = local device supports next page
+    pub const PHY_REG_ANER_LP_NP_ABLE: u16 = 1 << 3; // 1 = link partner supports next page
+    pub const PHY_REG_ANER_PDF: u16 = 1 << 4; // 1 = parallel detection fault, latching
+}
+
+use self::consts::*;
+use crate::smi::SMI;
+
+/// Identity of a PHY as read from the PHYIDR1/PHYIDR2 registers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhyId {
+    /// Bits 3 to 24 of the manufacturer's OUI
+    pub oui: u32,
+    /// Manufacturer's model number
+    pub model: u8,
+    /// Manufacturer's revision number
+    pub revision: u8,
+}
+
+impl PhyId {
+    /// Decode the contents of PHYIDR1 and PHYIDR2
+    pub(crate) fn from_registers(id1: u16, id2: u16) -> Self {
+        PhyId {
+            oui: ((id1 as u32) << 6) | ((id2 >> 10) as u32),
+            model: ((id2 >> 4) & 0x3F) as u8,
+            revision: (id2 & 0xF) as u8,
+        }
+    }
+}
+
+/// A PHY operation, or stopping the DMA engines, did not complete
+/// in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeout;
+
+/// How many times to poll for completion of a PHY reset
+pub(crate) const RESET_TIMEOUT_POLLS: u32 = 100_000;
+
+/// Abilities advertised by the link partner during auto-negotiation
+/// (ANLPAR register)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinkPartnerAbilities {
+    anlpar: u16,
+}
+
+impl LinkPartnerAbilities {
+    pub(crate) fn new(anlpar: u16) -> Self {
+        LinkPartnerAbilities { anlpar }
+    }
+
+    fn has(&self, bit: u16) -> bool {
+        (self.anlpar & bit) == bit
+    }
+
+    /// 10BASE-T half duplex
+    pub fn ten_half_duplex(&self) -> bool {
+        self.has(PHY_REG_AN_10_HALF)
+    }
+
+    /// 10BASE-T full duplex
+    pub fn ten_full_duplex(&self) -> bool {
+        self.has(PHY_REG_AN_10_FULL)
+    }
+
+    /// 100BASE-TX half duplex
+    pub fn hundred_half_duplex(&self) -> bool {
+        self.has(PHY_REG_AN_100_HALF)
+    }
+
+    /// 100BASE-TX full duplex
+    pub fn hundred_full_duplex(&self) -> bool {
+        self.has(PHY_REG_AN_100_FULL)
+    }
+
+    /// Symmetric pause frames
+    pub fn pause(&self) -> bool {
+        self.has(PHY_REG_AN_PAUSE)
+    }
+
+    /// Asymmetric pause frames
+    pub fn asymmetric_pause(&self) -> bool {
+        self.has(PHY_REG_AN_ASYM_PAUSE)
+    }
+
+    /// Link partner signals a remote fault
+    pub fn remote_fault(&self) -> bool {
+        self.has(PHY_REG_AN_REMOTE_FAULT)
+    }
+}
+
+/// Abilities to advertise during auto-negotiation (ANAR register)
+///
+/// `AdvertisedModes::default()` advertises all 10/100 Mbps modes,
+/// and no pause frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdvertisedModes {
+    /// 10BASE-T half duplex
+    pub ten_half_duplex: bool,
+    /// 10BASE-T full duplex
+    pub ten_full_duplex: bool,
+    /// 100BASE-TX half duplex
+    pub hundred_half_duplex: bool,
+    /// 100BASE-TX full duplex
+    pub hundred_full_duplex: bool,
+    /// Symmetric pause frames
+    pub pause: bool,
+    /// Asymmetric pause frames
+    pub asymmetric_pause: bool,
+}
+
+impl Default for AdvertisedModes {
+    fn default() -> Self {
+        AdvertisedModes {
+            ten_half_duplex: true,
+            ten_full_duplex: true,
+            hundred_half_duplex: true,
+            hundred_full_duplex: true,
+            pause: false,
+            asymmetric_pause: false,
+        }
+    }
+}
+
+impl AdvertisedModes {
+    /// Value of the ANAR register
+    fn anar(&self) -> u16 {
+        let abilities = [
+            (self.ten_half_duplex, PHY_REG_AN_10_HALF),
+            (self.ten_full_duplex, PHY_REG_AN_10_FULL),
+            (self.hundred_half_duplex, PHY_REG_AN_100_HALF),
+            (self.hundred_full_duplex, PHY_REG_AN_100_FULL),
+            (self.pause, PHY_REG_AN_PAUSE),
+            (self.asymmetric_pause, PHY_REG_AN_ASYM_PAUSE),
+        ];
+        abilities
+            .iter()
+            .filter(|(advertised, _)| *advertised)
+            .fold(PHY_REG_AN_SELECTOR_802_3, |anar, (_, bit)| anar | bit)
+    }
+}
+
+/// Write the ANAR register and restart auto-negotiation, so that the
+/// link is renegotiated with `modes`
+pub(crate) fn set_advertised(smi: &SMI, phy: u8, modes: AdvertisedModes) {
+    smi.write(phy, PHY_REG_ANAR, modes.anar());
+    smi.set_bits(
+        phy,
+        PHY_REG_BMCR,
+        PHY_REG_BMCR_AUTO_NEGOTIATION | PHY_REG_BMCR_RESTART_AUTONEG,
+    );
+}
+
+/// Read BMSR twice: the first read returns the latched link status
+/// and clears the latch, the second one the current link status
+///
+/// Returns whether the link went down since the last read, and
+/// whether it is up now.
+pub(crate) fn link_went_down(smi: &SMI, phy: u8) -> (bool, bool) {
+    let latched = smi.read(phy, PHY_REG_BMSR) & PHY_REG_BMSR_LINK_STATUS;
+    let current = smi.read(phy, PHY_REG_BMSR) & PHY_REG_BMSR_LINK_STATUS;
+    (latched == 0, current != 0)
+}
+
+/// Address MMD `device` through MMDAD
+fn select_mmd(smi: &SMI, phy: u8, device: u8) -> u16 {
+    let devad = u16::from(device) & PHY_REG_MMDCTRL_DEVAD;
+    smi.write(phy, PHY_REG_MMDCTRL, PHY_REG_MMDCTRL_ADDRESS | devad);
+    devad
+}
+
+/// Read an MMD register through the clause 22 indirect access
+/// registers
+pub(crate) fn read_mmd(smi: &SMI, phy: u8, device: u8, reg: u16) -> u16 {
+    let devad = select_mmd(smi, phy, device);
+    smi.write(phy, PHY_REG_MMDAD, reg);
+    smi.write(phy, PHY_REG_MMDCTRL, PHY_REG_MMDCTRL_DATA | devad);
+    smi.read(phy, PHY_REG_MMDAD)
+}
+
+/// Write an MMD register through the clause 22 indirect access
+/// registers
+pub(crate) fn write_mmd(smi: &SMI, phy: u8, device: u8, reg: u16, data: u16) {
+    let devad = select_mmd(smi, phy, device);
+    smi.write(phy, PHY_REG_MMDAD, reg);
+    smi.write(phy, PHY_REG_MMDCTRL, PHY_REG_MMDCTRL_DATA | devad);
+    smi.write(phy, PHY_REG_MMDAD, data);
+}
+
+/// Auto-negotiation expansion status (ANER register)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutonegExpansion {
+    aner: u16,
+}
+
+impl AutonegExpansion {
+    pub(crate) fn new(aner: u16) -> Self {
+        AutonegExpansion { aner }
+    }
+
+    fn has(&self, bit: u16) -> bool {
+        (self.aner & bit) == bit
+    }
+
+    /// Does the link partner support auto-negotiation at all?
+    pub fn link_partner_autoneg_able(&self) -> bool {
+        self.has(PHY_REG_ANER_LP_AN_ABLE)
+    }
+
+    /// Has a new link code word been received?
+    pub fn page_received(&self) -> bool {
+        self.has(PHY_REG_ANER_PAGE_RX)
+    }
+
+    /// Does the local PHY support next page exchange?
+    pub fn next_page_able(&self) -> bool {
+        self.has(PHY_REG_ANER_NP_ABLE)
+    }
+
+    /// Does the link partner support next page exchange?
+    pub fn link_partner_next_page_able(&self) -> bool {
+        self.has(PHY_REG_ANER_LP_NP_ABLE)
+    }
+
+    /// Did parallel detection find more than one usable technology?
+    pub fn parallel_detection_fault(&self) -> bool {
+        self.has(PHY_REG_ANER_PDF)
+    }
+}
+
+/// Link speed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Speed {
+    /// 10 Mbps
+    TenMbps,
+    /// 100 Mbps
+    HundredMbps,
+}
+
+/// Duplex mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Duplex {
+    Half,
+    Full,
+}
@@ -1,3 +1,4 @@
+use core::fmt;
 use core::option::Option;
 
 #[cfg(feature = "stm32f107")]
@@ -7,6 +8,11 @@ use stm32f4xx_hal::stm32::ethernet_mac::{MACMIIAR, MACMIIDR};
 
 use crate::smi::SMI;
 
+pub use crate::phy_common::{
+    AdvertisedModes, AutonegExpansion, Duplex, LinkPartnerAbilities, PhyId, Speed, Timeout,
+};
+use crate::phy_common::RESET_TIMEOUT_POLLS;
+
 #[allow(dead_code)]
 mod consts {
     // Some of these constants seems to be common to all Phy:s?
@@ -26,9 +32,14 @@ mod consts {
     pub const PHY_REG_LEDCR: u8 = 0x18; // LED Direct Control Register
     pub const PHY_REG_PHYCR: u8 = 0x19; // PHY Control Register
     pub const PHY_REG_10BTSCR: u8 = 0x1A; // 10Base-T Status/Control Register
+    // Only controls the 10BASE-T test patterns and the BIST error
+    // counter. The DP83848 has no TDR, so it cannot locate cable faults.
     pub const PHY_REG_CDCTRL1: u8 = 0x1B; // CD Test Control Register and BIST Extensions Register
     pub const PHY_REG_EDCR: u8 = 0x1D; // Energy Detect Control Register
 
+    pub const PHY_OUI: u32 = 0x08_0017;
+    pub const PHY_MODEL: u8 = 0x09;
+
     pub const PHY_REG_BMCR_RESET: u16 = 1 << 15; // 1 = reset, self-clearing upon completed reset
     pub const PHY_REG_BMCR_LOOPBACK: u16 = 1 << 14;
     pub const PHY_REG_BMCR_SPEED_SELECTION: u16 = 1 << 13; // 1 = 100Mb/s, 0 = 10 Mb/s
@@ -47,6 +58,17 @@ mod consts {
     pub const PHY_REG_BMSR_AUTONEG_COMPLETE: u16 = 1 << 5; // 1 = complete
     pub const PHY_REG_BMSR_REMOTE_FAULT: u16 = 1 << 4; // 1 = Remote Fault condition detected
     pub const PHY_REG_BMSR_LINK_STATUS: u16 = 1 << 2; // 1 = valid link
+
+    pub const PHY_REG_PHYSTS_DUPLEX: u16 = 1 << 2; // 1 = full duplex
+    pub const PHY_REG_PHYSTS_SPEED: u16 = 1 << 1; // 1 = 10 Mb/s, 0 = 100 Mb/s
+
+    pub const PHY_REG_PHYCR_LED_CNFG: u16 = 0b11 << 5; // LED mode selection
+    pub const PHY_REG_PHYCR_LED_CNFG_MODE1: u16 = 0b01 << 5;
+    pub const PHY_REG_PHYCR_LED_CNFG_MODE2: u16 = 0b00 << 5;
+    pub const PHY_REG_PHYCR_LED_CNFG_MODE3: u16 = 0b10 << 5;
+
+    pub const PHY_REG_LEDCR_DRV_LNKLED: u16 = 1 << 4; // 1 = drive LED_LINK from LNKLED
+    pub const PHY_REG_LEDCR_LNKLED: u16 = 1 << 1; // Value to drive on LED_LINK
 }
 
 use self::consts::*;
@@ -64,6 +86,47 @@ impl<'a> Phy<'a> {
         Phy { smi, phy }
     }
 
+    /// Read the identifier registers
+    pub fn identify(&self) -> PhyId {
+        PhyId::from_registers(
+            self.smi.read(self.phy, PHY_REG_PHYIDR1),
+            self.smi.read(self.phy, PHY_REG_PHYIDR2),
+        )
+    }
+
+    /// Read the abilities that the link partner advertised during
+    /// auto-negotiation
+    pub fn link_partner_abilities(&self) -> LinkPartnerAbilities {
+        LinkPartnerAbilities::new(self.smi.read(self.phy, PHY_REG_ANLPAR))
+    }
+
+    /// Read the auto-negotiation expansion register
+    ///
+    /// The page received and parallel detection fault bits are
+    /// cleared by reading.
+    pub fn autoneg_expansion(&self) -> AutonegExpansion {
+        AutonegExpansion::new(self.smi.read(self.phy, PHY_REG_ANER))
+    }
+
+    /// Has the link been down since the status register was last
+    /// read, and is it up now?
+    ///
+    /// The link status bit latches low: after the link has dropped,
+    /// it reads as down once, even if the link came back up in the
+    /// meantime. The status register is therefore read twice, the
+    /// first read reporting and clearing the latch and the second one
+    /// reporting the current link state. Note that
+    /// [`status()`](#method.status) clears the latch as well, so any
+    /// call to it resets what this method reports.
+    pub fn link_went_down_since_last_read(&self) -> (bool, bool) {
+        crate::phy_common::link_went_down(&self.smi, self.phy)
+    }
+
+    /// Does a PHY of this type respond at this address?
+    pub fn is_present(&self) -> bool {
+        is_supported(&self.identify())
+    }
+
     /// Read current status registers
     ///
     /// You may keep the returned [`PhyStatus`](struct.PhyStatus.html)
@@ -71,17 +134,76 @@ impl<'a> Phy<'a> {
     pub fn status(&self) -> PhyStatus {
         PhyStatus {
             bmsr: self.smi.read(self.phy, PHY_REG_BMSR),
+            physts: self.smi.read(self.phy, PHY_REG_PHYSTS),
         }
     }
 
     /// Reset the PHY
-    pub fn reset(&self) -> &Self {
+    ///
+    /// Fails if the PHY does not complete the reset in time, e.g.
+    /// because there is no PHY at this address.
+    pub fn reset(&self) -> Result<&Self, Timeout> {
         self.smi
             .set_bits(self.phy, PHY_REG_BMCR, PHY_REG_BMCR_RESET);
 
         // wait until reset bit is cleared by phy
-        while (self.smi.read(self.phy, PHY_REG_BMCR) & PHY_REG_BMCR_RESET) == PHY_REG_BMCR_RESET {}
+        for _ in 0..RESET_TIMEOUT_POLLS {
+            if (self.smi.read(self.phy, PHY_REG_BMCR) & PHY_REG_BMCR_RESET) != PHY_REG_BMCR_RESET {
+                return Ok(self);
+            }
+        }
 
+        Err(Timeout)
+    }
+
+    /// Select what the LEDs indicate
+    pub fn set_led_mode(&self, mode: LedMode) -> &Self {
+        let bits = match mode {
+            LedMode::Mode1 => PHY_REG_PHYCR_LED_CNFG_MODE1,
+            LedMode::Mode2 => PHY_REG_PHYCR_LED_CNFG_MODE2,
+            LedMode::Mode3 => PHY_REG_PHYCR_LED_CNFG_MODE3,
+        };
+        let phycr = self.smi.read(self.phy, PHY_REG_PHYCR);
+        self.smi.write(
+            self.phy,
+            PHY_REG_PHYCR,
+            (phycr & !PHY_REG_PHYCR_LED_CNFG) | bits,
+        );
+        self
+    }
+
+    /// Take over the link LED, e.g. to flash it for identifying the
+    /// board
+    ///
+    /// The LED pins are active low, so `on` drives `LED_LINK` low.
+    /// Use [`release_led()`](#method.release_led) to let it
+    /// indicate the link again.
+    pub fn force_led(&self, on: bool) -> &Self {
+        let value = if on { 0 } else { PHY_REG_LEDCR_LNKLED };
+        let ledcr = self.smi.read(self.phy, PHY_REG_LEDCR);
+        self.smi.write(
+            self.phy,
+            PHY_REG_LEDCR,
+            (ledcr & !PHY_REG_LEDCR_LNKLED) | PHY_REG_LEDCR_DRV_LNKLED | value,
+        );
+        self
+    }
+
+    /// Let the link LED indicate the link again after
+    /// [`force_led()`](#method.force_led)
+    pub fn release_led(&self) -> &Self {
+        self.smi
+            .clear_bits(self.phy, PHY_REG_LEDCR, PHY_REG_LEDCR_DRV_LNKLED);
+        self
+    }
+
+    /// Advertise only `modes` and restart auto-negotiation
+    ///
+    /// E.g. to keep a long cable at 10 Mbps, while still
+    /// negotiating with the link partner instead of falling back to
+    /// parallel detection.
+    pub fn set_advertised(&self, modes: AdvertisedModes) -> &Self {
+        crate::phy_common::set_advertised(&self.smi, self.phy, modes);
         self
     }
 
@@ -98,10 +220,43 @@ impl<'a> Phy<'a> {
     }
 }
 
+/// LED configuration, see the `LED_CFG` strap option in the
+/// datasheet for what each LED shows in which mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedMode {
+    /// `LED_LINK` shows link, `LED_SPEED` speed, and `LED_ACT`
+    /// activity
+    Mode1,
+    /// `LED_LINK` shows link and activity, `LED_SPEED` speed, and
+    /// `LED_ACT` collisions
+    Mode2,
+    /// `LED_LINK` shows link and activity, `LED_SPEED` speed, and
+    /// `LED_ACT` the duplex mode
+    Mode3,
+}
+
+/// How long to hold the hardware reset pin low, in µs
+///
+/// The DP83848 requires at least 1 µs.
+pub const RESET_PULSE_US: u32 = 10;
+
+/// Can the PHY be connected via MII?
+///
+/// The DP83848 supports both MII and RMII.
+pub const SUPPORTS_MII: bool = true;
+
+/// Is `id` the identity of a DP83848?
+///
+/// The revision is ignored.
+pub fn is_supported(id: &PhyId) -> bool {
+    id.oui == PHY_OUI && id.model == PHY_MODEL
+}
+
 /// PHY status register
 #[derive(Copy, Clone)]
 pub struct PhyStatus {
     bmsr: u16,
+    physts: u16,
 }
 
 impl PhyStatus {
@@ -116,34 +271,36 @@ impl PhyStatus {
     }
 
     /// FD, not HD?
+    ///
+    /// This is the negotiated mode from the PHYSTS register, not one
+    /// of the abilities in BMSR.
     pub fn is_full_duplex(&self) -> Option<bool> {
-        if (self.bmsr & PHY_REG_BMSR_LINK_STATUS) != PHY_REG_BMSR_LINK_STATUS {
+        if !self.link_detected() {
             return None;
         }
-        if (self.bmsr & PHY_REG_BMSR_100_FULL) == PHY_REG_BMSR_100_FULL
-            || (self.bmsr & PHY_REG_BMSR_10_FULL) == PHY_REG_BMSR_10_FULL
-        {
-            return Some(true);
-        }
-        return Some(false);
+        Some((self.physts & PHY_REG_PHYSTS_DUPLEX) == PHY_REG_PHYSTS_DUPLEX)
     }
 
-    /// 10, 100, or 0 Mbps
-    pub fn speed(&self) -> u32 {
-        if (self.bmsr & PHY_REG_BMSR_LINK_STATUS) != PHY_REG_BMSR_LINK_STATUS {
-            return 0;
+    /// 10 or 100 Mbps, or `None` without link
+    pub fn speed(&self) -> Option<Speed> {
+        if !self.link_detected() {
+            return None;
         }
-        if (self.bmsr & PHY_REG_BMSR_100_FULL) == PHY_REG_BMSR_100_FULL
-            || (self.bmsr & PHY_REG_BMSR_100_HALF) == PHY_REG_BMSR_100_HALF
-        {
-            return 100;
+        if (self.physts & PHY_REG_PHYSTS_SPEED) == PHY_REG_PHYSTS_SPEED {
+            Some(Speed::TenMbps)
+        } else {
+            Some(Speed::HundredMbps)
         }
-        if (self.bmsr & PHY_REG_BMSR_10_FULL) == PHY_REG_BMSR_10_FULL
-            || (self.bmsr & PHY_REG_BMSR_10_HALF) == PHY_REG_BMSR_10_HALF
-        {
-            return 10;
+    }
+
+    /// 10, 100, or 0 Mbps
+    #[deprecated(note = "Use `speed()`, which returns `None` instead of 0 without link")]
+    pub fn speed_mbps(&self) -> u32 {
+        match self.speed() {
+            Some(Speed::TenMbps) => 10,
+            Some(Speed::HundredMbps) => 100,
+            None => 0,
         }
-        return 0;
     }
 
     /// Error?
@@ -162,3 +319,17 @@ impl PartialEq for PhyStatus {
                 && self.speed() == other.speed())
     }
 }
+
+/// Shows link, speed, and duplex mode, as interpreted from the
+/// registers.
+impl fmt::Debug for PhyStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PhyStatus")
+            .field("link_detected", &self.link_detected())
+            .field("autoneg_done", &self.autoneg_done())
+            .field("speed", &self.speed())
+            .field("full_duplex", &self.is_full_duplex())
+            .field("remote_fault", &self.remote_fault())
+            .finish()
+    }
+}
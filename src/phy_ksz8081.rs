@@ -0,0 +1,292 @@
+use core::fmt;
+use core::option::Option;
+
+#[cfg(feature = "stm32f107")]
+use stm32f1::stm32f107::ethernet_mac::{MACMIIAR, MACMIIDR};
+#[cfg(feature = "stm32f4xx")]
+use stm32f4xx_hal::stm32::ethernet_mac::{MACMIIAR, MACMIIDR};
+
+use crate::smi::SMI;
+
+pub use crate::phy_common::{
+    AdvertisedModes, AutonegExpansion, Duplex, LinkPartnerAbilities, PhyId, Speed, Timeout,
+};
+use crate::phy_common::RESET_TIMEOUT_POLLS;
+
+#[allow(dead_code)]
+mod consts {
+    pub use crate::phy_common::consts::*;
+
+    pub const PHY_REG_AFECR1: u8 = 0x11; // AFE Control 1 Register
+    pub const PHY_REG_RXERCR: u8 = 0x15; // RXER Counter Register
+    pub const PHY_REG_OMSOR: u8 = 0x16; // Operation Mode Strap Override Register
+    pub const PHY_REG_OMSSR: u8 = 0x17; // Operation Mode Strap Status Register
+    pub const PHY_REG_ECR: u8 = 0x18; // Expanded Control Register
+    pub const PHY_REG_ICSR: u8 = 0x1B; // Interrupt Control/Status Register
+    pub const PHY_REG_LINKMDCSR: u8 = 0x1D; // LinkMD Control/Status Register
+    pub const PHY_REG_PHYCTRL1: u8 = 0x1E; // PHY Control 1 Register
+    pub const PHY_REG_PHYCTRL2: u8 = 0x1F; // PHY Control 2 Register
+
+    pub const PHY_OUI: u32 = 0x00_0885;
+    pub const PHY_MODEL: u8 = 0x16;
+
+    pub const PHY_REG_PHYCTRL2_LED_MODE: u16 = 0b11 << 4; // LED mode selection
+    pub const PHY_REG_PHYCTRL2_LED_MODE_0: u16 = 0b00 << 4;
+    pub const PHY_REG_PHYCTRL2_LED_MODE_1: u16 = 0b01 << 4;
+
+    pub const PHY_REG_PHYCTRL1_LINK_STATUS: u16 = 1 << 8; // 1 = link is up
+    pub const PHY_REG_PHYCTRL1_OP_MODE: u16 = 0b111; // Operation mode indication
+    pub const PHY_REG_PHYCTRL1_OP_MODE_AUTONEG: u16 = 0b000; // Still in auto-negotiation
+    pub const PHY_REG_PHYCTRL1_OP_MODE_10BASE_HD: u16 = 0b001;
+    pub const PHY_REG_PHYCTRL1_OP_MODE_100BASE_HD: u16 = 0b010;
+    pub const PHY_REG_PHYCTRL1_OP_MODE_10BASE_FD: u16 = 0b101;
+    pub const PHY_REG_PHYCTRL1_OP_MODE_100BASE_FD: u16 = 0b110;
+}
+
+use self::consts::*;
+
+pub struct Phy<'a> {
+    smi: SMI<'a>,
+    phy: u8,
+}
+
+impl<'a> Phy<'a> {
+    /// Allocate
+    pub fn new(macmiiar: &'a MACMIIAR, macmiidr: &'a MACMIIDR, phy: u8) -> Self {
+        let smi = SMI::new(macmiiar, macmiidr);
+
+        Phy { smi, phy }
+    }
+
+    /// Read the identifier registers
+    pub fn identify(&self) -> PhyId {
+        PhyId::from_registers(
+            self.smi.read(self.phy, PHY_REG_PHYIDR1),
+            self.smi.read(self.phy, PHY_REG_PHYIDR2),
+        )
+    }
+
+    /// Read the abilities that the link partner advertised during
+    /// auto-negotiation
+    pub fn link_partner_abilities(&self) -> LinkPartnerAbilities {
+        LinkPartnerAbilities::new(self.smi.read(self.phy, PHY_REG_ANLPAR))
+    }
+
+    /// Read the auto-negotiation expansion register
+    ///
+    /// The page received and parallel detection fault bits are
+    /// cleared by reading.
+    pub fn autoneg_expansion(&self) -> AutonegExpansion {
+        AutonegExpansion::new(self.smi.read(self.phy, PHY_REG_ANER))
+    }
+
+    /// Has the link been down since the status register was last
+    /// read, and is it up now?
+    ///
+    /// The link status bit latches low: after the link has dropped,
+    /// it reads as down once, even if the link came back up in the
+    /// meantime. The status register is therefore read twice, the
+    /// first read reporting and clearing the latch and the second one
+    /// reporting the current link state. Note that
+    /// [`status()`](#method.status) clears the latch as well, so any
+    /// call to it resets what this method reports.
+    pub fn link_went_down_since_last_read(&self) -> (bool, bool) {
+        crate::phy_common::link_went_down(&self.smi, self.phy)
+    }
+
+    /// Does a PHY of this type respond at this address?
+    pub fn is_present(&self) -> bool {
+        is_supported(&self.identify())
+    }
+
+    /// Read current status registers
+    ///
+    /// You may keep the returned [`PhyStatus`](struct.PhyStatus.html)
+    /// to compare it with to a future [`status()`](#method.status).
+    pub fn status(&self) -> PhyStatus {
+        PhyStatus {
+            bmsr: self.smi.read(self.phy, PHY_REG_BMSR),
+            phyctrl1: self.smi.read(self.phy, PHY_REG_PHYCTRL1),
+        }
+    }
+
+    /// Reset the PHY
+    ///
+    /// Fails if the PHY does not complete the reset in time, e.g.
+    /// because there is no PHY at this address.
+    pub fn reset(&self) -> Result<&Self, Timeout> {
+        self.smi
+            .set_bits(self.phy, PHY_REG_BMCR, PHY_REG_BMCR_RESET);
+
+        // wait until reset bit is cleared by phy
+        for _ in 0..RESET_TIMEOUT_POLLS {
+            if (self.smi.read(self.phy, PHY_REG_BMCR) & PHY_REG_BMCR_RESET) != PHY_REG_BMCR_RESET {
+                return Ok(self);
+            }
+        }
+
+        Err(Timeout)
+    }
+
+    /// Select what the LEDs indicate
+    pub fn set_led_mode(&self, mode: LedMode) -> &Self {
+        let bits = match mode {
+            LedMode::Mode0 => PHY_REG_PHYCTRL2_LED_MODE_0,
+            LedMode::Mode1 => PHY_REG_PHYCTRL2_LED_MODE_1,
+        };
+        let phyctrl2 = self.smi.read(self.phy, PHY_REG_PHYCTRL2);
+        self.smi.write(
+            self.phy,
+            PHY_REG_PHYCTRL2,
+            (phyctrl2 & !PHY_REG_PHYCTRL2_LED_MODE) | bits,
+        );
+        self
+    }
+
+    /// Read register `reg` of the MDIO manageable device `device`
+    /// (MMD), through the indirect access registers
+    pub fn read_mmd(&self, device: u8, reg: u16) -> u16 {
+        crate::phy_common::read_mmd(&self.smi, self.phy, device, reg)
+    }
+
+    /// Write register `reg` of the MMD `device`, through the
+    /// indirect access registers
+    pub fn write_mmd(&self, device: u8, reg: u16, data: u16) -> &Self {
+        crate::phy_common::write_mmd(&self.smi, self.phy, device, reg, data);
+        self
+    }
+
+    /// Advertise only `modes` and restart auto-negotiation
+    ///
+    /// E.g. to keep a long cable at 10 Mbps, while still
+    /// negotiating with the link partner instead of falling back to
+    /// parallel detection.
+    pub fn set_advertised(&self, modes: AdvertisedModes) -> &Self {
+        crate::phy_common::set_advertised(&self.smi, self.phy, modes);
+        self
+    }
+
+    /// Enable 10/100 Mbps half/full-duplex auto-negotiation
+    pub fn set_autoneg(&self) -> &Self {
+        self.smi.set_bits(
+            self.phy,
+            PHY_REG_BMCR,
+            PHY_REG_BMCR_AUTO_NEGOTIATION | PHY_REG_BMCR_RESTART_AUTONEG,
+        );
+
+        self
+    }
+}
+
+/// LED configuration
+///
+/// The KSZ8081 cannot drive its LEDs directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedMode {
+    /// `LED0` shows link and activity, `LED1` speed
+    Mode0,
+    /// `LED0` shows link, `LED1` activity
+    Mode1,
+}
+
+/// How long to hold the hardware reset pin low, in µs
+///
+/// The KSZ8081 requires at least 500 µs.
+pub const RESET_PULSE_US: u32 = 1000;
+
+/// Can the PHY be connected via MII?
+///
+/// MII is supported by the KSZ8081MNX/MLX variants, RMII by the KSZ8081RNx ones.
+pub const SUPPORTS_MII: bool = true;
+
+/// Is `id` the identity of a KSZ8081?
+///
+/// The revision is ignored.
+pub fn is_supported(id: &PhyId) -> bool {
+    id.oui == PHY_OUI && id.model == PHY_MODEL
+}
+
+/// PHY status register
+#[derive(Copy, Clone)]
+pub struct PhyStatus {
+    bmsr: u16,
+    phyctrl1: u16,
+}
+
+impl PhyStatus {
+    /// Has link?
+    pub fn link_detected(&self) -> bool {
+        (self.bmsr & PHY_REG_BMSR_LINK_STATUS) == PHY_REG_BMSR_LINK_STATUS
+    }
+
+    /// Has auto-negotiated?
+    pub fn autoneg_done(&self) -> bool {
+        (self.bmsr & PHY_REG_BMSR_AUTONEG_COMPLETE) == PHY_REG_BMSR_AUTONEG_COMPLETE
+    }
+
+    /// FD, not HD?
+    pub fn is_full_duplex(&self) -> Option<bool> {
+        match self.phyctrl1 & PHY_REG_PHYCTRL1_OP_MODE {
+            PHY_REG_PHYCTRL1_OP_MODE_10BASE_HD | PHY_REG_PHYCTRL1_OP_MODE_100BASE_HD => {
+                Some(false)
+            }
+            PHY_REG_PHYCTRL1_OP_MODE_10BASE_FD | PHY_REG_PHYCTRL1_OP_MODE_100BASE_FD => {
+                Some(true)
+            }
+            _ => None,
+        }
+    }
+
+    /// 10 or 100 Mbps, or `None` without link
+    pub fn speed(&self) -> Option<Speed> {
+        match self.phyctrl1 & PHY_REG_PHYCTRL1_OP_MODE {
+            PHY_REG_PHYCTRL1_OP_MODE_10BASE_HD | PHY_REG_PHYCTRL1_OP_MODE_10BASE_FD => {
+                Some(Speed::TenMbps)
+            }
+            PHY_REG_PHYCTRL1_OP_MODE_100BASE_HD | PHY_REG_PHYCTRL1_OP_MODE_100BASE_FD => {
+                Some(Speed::HundredMbps)
+            }
+            _ => None,
+        }
+    }
+
+    /// 10, 100, or 0 Mbps
+    #[deprecated(note = "Use `speed()`, which returns `None` instead of 0 without link")]
+    pub fn speed_mbps(&self) -> u32 {
+        match self.speed() {
+            Some(Speed::TenMbps) => 10,
+            Some(Speed::HundredMbps) => 100,
+            None => 0,
+        }
+    }
+
+    /// Error?
+    pub fn remote_fault(&self) -> bool {
+        (self.bmsr & PHY_REG_BMSR_REMOTE_FAULT) == PHY_REG_BMSR_REMOTE_FAULT
+    }
+}
+
+/// Compare on base of link detected, full-duplex, and speed
+/// attributes.
+impl PartialEq for PhyStatus {
+    fn eq(&self, other: &PhyStatus) -> bool {
+        (self.link_detected() == false && other.link_detected() == false)
+            || (self.link_detected() == other.link_detected()
+                && self.is_full_duplex() == other.is_full_duplex()
+                && self.speed() == other.speed())
+    }
+}
+
+/// Shows link, speed, and duplex mode, as interpreted from the
+/// registers.
+impl fmt::Debug for PhyStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PhyStatus")
+            .field("link_detected", &self.link_detected())
+            .field("autoneg_done", &self.autoneg_done())
+            .field("speed", &self.speed())
+            .field("full_duplex", &self.is_full_duplex())
+            .field("remote_fault", &self.remote_fault())
+            .finish()
+    }
+}
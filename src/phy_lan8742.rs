@@ -1,3 +1,4 @@
+use core::fmt;
 use core::option::Option;
 
 #[cfg(feature = "stm32f107")]
@@ -7,6 +8,11 @@ use stm32f4xx_hal::stm32::ethernet_mac::{MACMIIAR, MACMIIDR};
 
 use crate::smi::SMI;
 
+pub use crate::phy_common::{
+    AdvertisedModes, AutonegExpansion, Duplex, LinkPartnerAbilities, PhyId, Speed, Timeout,
+};
+use crate::phy_common::RESET_TIMEOUT_POLLS;
+
 #[allow(dead_code)]
 mod consts {
     pub const PHY_REG_BCR: u8 = 0x00;
@@ -18,8 +24,12 @@ mod consts {
     pub const PHY_REG_ANEXP: u8 = 0x06;
     pub const PHY_REG_ANNPTX: u8 = 0x07;
     pub const PHY_REG_ANNPRX: u8 = 0x08;
+    pub const PHY_REG_MCSR: u8 = 0x11; // Mode Control/Status Register
     pub const PHY_REG_SSR: u8 = 0x1F; // Special Status Register
 
+    pub const PHY_OUI: u32 = 0x00_01F0;
+    pub const PHY_MODEL: u8 = 0x13;
+
     pub const PHY_REG_BCR_COLTEST: u16 = 1 << 7;
     pub const PHY_REG_BCR_FD: u16 = 1 << 8;
     pub const PHY_REG_BCR_ANRST: u16 = 1 << 9;
@@ -35,6 +45,8 @@ mod consts {
     pub const PHY_REG_BSR_FAULT: u16 = 1 << 4;
     pub const PHY_REG_BSR_ANDONE: u16 = 1 << 5;
 
+    pub const PHY_REG_MCSR_EDPWRDOWN: u16 = 1 << 13; // Energy Detect Power-Down
+
     pub const PHY_REG_SSR_ANDONE: u16 = 1 << 12;
     pub const PHY_REG_SSR_SPEED: u16 = 0b111 << 2;
     pub const PHY_REG_SSR_10BASE_HD: u16 = 0b001 << 2;
@@ -58,6 +70,47 @@ impl<'a> Phy<'a> {
         Phy { smi, phy }
     }
 
+    /// Read the identifier registers
+    pub fn identify(&self) -> PhyId {
+        PhyId::from_registers(
+            self.smi.read(self.phy, PHY_REG_ID1),
+            self.smi.read(self.phy, PHY_REG_ID2),
+        )
+    }
+
+    /// Read the abilities that the link partner advertised during
+    /// auto-negotiation
+    pub fn link_partner_abilities(&self) -> LinkPartnerAbilities {
+        LinkPartnerAbilities::new(self.smi.read(self.phy, PHY_REG_ANRX))
+    }
+
+    /// Read the auto-negotiation expansion register
+    ///
+    /// The page received and parallel detection fault bits are
+    /// cleared by reading.
+    pub fn autoneg_expansion(&self) -> AutonegExpansion {
+        AutonegExpansion::new(self.smi.read(self.phy, PHY_REG_ANEXP))
+    }
+
+    /// Has the link been down since the status register was last
+    /// read, and is it up now?
+    ///
+    /// The link status bit latches low: after the link has dropped,
+    /// it reads as down once, even if the link came back up in the
+    /// meantime. The status register is therefore read twice, the
+    /// first read reporting and clearing the latch and the second one
+    /// reporting the current link state. Note that
+    /// [`status()`](#method.status) clears the latch as well, so any
+    /// call to it resets what this method reports.
+    pub fn link_went_down_since_last_read(&self) -> (bool, bool) {
+        crate::phy_common::link_went_down(&self.smi, self.phy)
+    }
+
+    /// Does a PHY of this type respond at this address?
+    pub fn is_present(&self) -> bool {
+        is_supported(&self.identify())
+    }
+
     /// Read current status registers
     ///
     /// You may keep the returned [`PhyStatus`](struct.PhyStatus.html)
@@ -70,12 +123,42 @@ impl<'a> Phy<'a> {
     }
 
     /// Reset the PHY
-    pub fn reset(&self) -> &Self {
+    ///
+    /// Fails if the PHY does not complete the reset in time, e.g.
+    /// because there is no PHY at this address.
+    pub fn reset(&self) -> Result<&Self, Timeout> {
         self.smi.set_bits(self.phy, PHY_REG_BCR, PHY_REG_BCR_RESET);
 
         // wait until reset bit is cleared by phy
-        while (self.smi.read(self.phy, PHY_REG_BCR) & PHY_REG_BCR_RESET) == PHY_REG_BCR_RESET {}
+        for _ in 0..RESET_TIMEOUT_POLLS {
+            if (self.smi.read(self.phy, PHY_REG_BCR) & PHY_REG_BCR_RESET) != PHY_REG_BCR_RESET {
+                return Ok(self);
+            }
+        }
+
+        Err(Timeout)
+    }
+
+    /// Read register `reg` of the MDIO manageable device `device`
+    /// (MMD), through the indirect access registers
+    pub fn read_mmd(&self, device: u8, reg: u16) -> u16 {
+        crate::phy_common::read_mmd(&self.smi, self.phy, device, reg)
+    }
+
+    /// Write register `reg` of the MMD `device`, through the
+    /// indirect access registers
+    pub fn write_mmd(&self, device: u8, reg: u16, data: u16) -> &Self {
+        crate::phy_common::write_mmd(&self.smi, self.phy, device, reg, data);
+        self
+    }
 
+    /// Advertise only `modes` and restart auto-negotiation
+    ///
+    /// E.g. to keep a long cable at 10 Mbps, while still
+    /// negotiating with the link partner instead of falling back to
+    /// parallel detection.
+    pub fn set_advertised(&self, modes: AdvertisedModes) -> &Self {
+        crate::phy_common::set_advertised(&self.smi, self.phy, modes);
         self
     }
 
@@ -89,6 +172,39 @@ impl<'a> Phy<'a> {
 
         self
     }
+
+    /// Enable or disable Energy Detect Power-Down (EDPD)
+    ///
+    /// While enabled, the PHY powers down its analog circuitry as
+    /// long as no link partner energy is detected on the line.
+    pub fn set_energy_detect(&self, enabled: bool) -> &Self {
+        if enabled {
+            self.smi
+                .set_bits(self.phy, PHY_REG_MCSR, PHY_REG_MCSR_EDPWRDOWN);
+        } else {
+            self.smi
+                .clear_bits(self.phy, PHY_REG_MCSR, PHY_REG_MCSR_EDPWRDOWN);
+        }
+
+        self
+    }
+}
+
+/// How long to hold the hardware reset pin low, in µs
+///
+/// The LAN8742A requires at least 100 µs.
+pub const RESET_PULSE_US: u32 = 200;
+
+/// Can the PHY be connected via MII?
+///
+/// The LAN8742A only has an RMII interface.
+pub const SUPPORTS_MII: bool = false;
+
+/// Is `id` the identity of an LAN8742?
+///
+/// The revision is ignored.
+pub fn is_supported(id: &PhyId) -> bool {
+    id.oui == PHY_OUI && id.model == PHY_MODEL
 }
 
 /// PHY status register
@@ -119,12 +235,22 @@ impl PhyStatus {
         }
     }
 
-    /// 10, 100, or 0 Mbps
-    pub fn speed(&self) -> u32 {
+    /// 10 or 100 Mbps, or `None` without link
+    pub fn speed(&self) -> Option<Speed> {
         match self.ssr & PHY_REG_SSR_SPEED {
-            PHY_REG_SSR_10BASE_HD | PHY_REG_SSR_10BASE_FD => 10,
-            PHY_REG_SSR_100BASE_HD | PHY_REG_SSR_100BASE_FD => 100,
-            _ => 0,
+            PHY_REG_SSR_10BASE_HD | PHY_REG_SSR_10BASE_FD => Some(Speed::TenMbps),
+            PHY_REG_SSR_100BASE_HD | PHY_REG_SSR_100BASE_FD => Some(Speed::HundredMbps),
+            _ => None,
+        }
+    }
+
+    /// 10, 100, or 0 Mbps
+    #[deprecated(note = "Use `speed()`, which returns `None` instead of 0 without link")]
+    pub fn speed_mbps(&self) -> u32 {
+        match self.speed() {
+            Some(Speed::TenMbps) => 10,
+            Some(Speed::HundredMbps) => 100,
+            None => 0,
         }
     }
 
@@ -144,3 +270,17 @@ impl PartialEq for PhyStatus {
                 && self.speed() == other.speed())
     }
 }
+
+/// Shows link, speed, and duplex mode, as interpreted from the
+/// registers.
+impl fmt::Debug for PhyStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PhyStatus")
+            .field("link_detected", &self.link_detected())
+            .field("autoneg_done", &self.autoneg_done())
+            .field("speed", &self.speed())
+            .field("full_duplex", &self.is_full_duplex())
+            .field("remote_fault", &self.remote_fault())
+            .finish()
+    }
+}
@@ -0,0 +1,113 @@
+//! RMII pin setup for STM32F4xx boards
+//!
+//! Each trait is implemented for the pins that can carry the
+//! respective signal. Pass the pins of your board to
+//! [`setup_rmii_pins()`](fn.setup_rmii_pins.html):
+//!
+//! ```ignore
+//! let gpioa = p.GPIOA.split();
+//! let gpiob = p.GPIOB.split();
+//! let gpioc = p.GPIOC.split();
+//! stm32_eth::pins::setup_rmii_pins(
+//!     gpioa.pa1, gpioa.pa2, gpioc.pc1, gpioa.pa7, gpioc.pc4, gpioc.pc5,
+//!     gpiob.pb11, gpiob.pb12, gpiob.pb13,
+//! );
+//! ```
+
+use stm32f4xx_hal::gpio::{
+    gpioa::{PA1, PA2, PA7},
+    gpiob::{PB11, PB12, PB13},
+    gpioc::{PC1, PC4, PC5},
+    gpiog::{PG11, PG13, PG14},
+    Speed::VeryHigh,
+};
+
+/// RMII reference clock
+pub trait RefClk {
+    fn setup(self);
+}
+/// SMI data
+pub trait Mdio {
+    fn setup(self);
+}
+/// SMI clock
+pub trait Mdc {
+    fn setup(self);
+}
+/// RMII carrier sense/receive data valid
+pub trait CrsDv {
+    fn setup(self);
+}
+/// RMII receive data bit 0
+pub trait RxD0 {
+    fn setup(self);
+}
+/// RMII receive data bit 1
+pub trait RxD1 {
+    fn setup(self);
+}
+/// RMII transmit enable
+pub trait TxEn {
+    fn setup(self);
+}
+/// RMII transmit data bit 0
+pub trait TxD0 {
+    fn setup(self);
+}
+/// RMII transmit data bit 1
+pub trait TxD1 {
+    fn setup(self);
+}
+
+macro_rules! impl_pins {
+    ($($trait:ident: [$($pin:ident),*],)*) => {
+        $($(
+            impl<MODE> $trait for $pin<MODE> {
+                fn setup(self) {
+                    self.into_alternate_af11().set_speed(VeryHigh);
+                }
+            }
+        )*)*
+    };
+}
+
+impl_pins! {
+    RefClk: [PA1],
+    Mdio: [PA2],
+    Mdc: [PC1],
+    CrsDv: [PA7],
+    RxD0: [PC4],
+    RxD1: [PC5],
+    TxEn: [PB11, PG11],
+    TxD0: [PB12, PG13],
+    TxD1: [PB13, PG14],
+}
+
+/// Set RMII pins to
+/// * Alternate function 11
+/// * High-speed
+///
+/// This function consumes the pins so that you cannot use them
+/// anywhere else by accident.
+#[allow(clippy::too_many_arguments)]
+pub fn setup_rmii_pins(
+    ref_clk: impl RefClk,
+    mdio: impl Mdio,
+    mdc: impl Mdc,
+    crs_dv: impl CrsDv,
+    rxd0: impl RxD0,
+    rxd1: impl RxD1,
+    tx_en: impl TxEn,
+    txd0: impl TxD0,
+    txd1: impl TxD1,
+) {
+    ref_clk.setup();
+    mdio.setup();
+    mdc.setup();
+    crs_dv.setup();
+    rxd0.setup();
+    rxd1.setup();
+    tx_en.setup();
+    txd0.setup();
+    txd1.setup();
+}
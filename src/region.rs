@@ -0,0 +1,59 @@
+//! Storage for the rings that can be placed in a linker section
+
+use core::mem::MaybeUninit;
+
+use crate::{rx::RxRingEntry, tx::TxRingEntry};
+
+/// Rx and Tx ring entries, descriptors and buffers, in one `static`
+///
+/// The DMA engine cannot access every memory region, e.g. not
+/// Core-Coupled Memory (CCM) on STM32F4xx. A `DmaRegion` needs only
+/// one `#[link_section]` attribute to be placed in a region that it
+/// can access, such as SRAM2 when the stack lives in CCM:
+///
+/// ```ignore
+/// #[link_section = ".sram2"]
+/// static mut DMA_REGION: DmaRegion<8, 2> = DmaRegion::new();
+///
+/// let (rx_ring, tx_ring) = unsafe { DMA_REGION.split() };
+/// let eth = Eth::new_with_arrays(mac, dma, rx_ring, tx_ring)?;
+/// ```
+///
+/// It is left uninitialized until [`split()`](#method.split), so
+/// that it may also go into a `NOLOAD` section that the runtime does
+/// not initialize. Whether the chosen region is actually
+/// DMA-accessible is checked when the `Eth` is created, which fails
+/// with `InitError::InaccessibleBuffer` otherwise.
+#[repr(C)]
+pub struct DmaRegion<const RX: usize, const TX: usize> {
+    rx: MaybeUninit<[RxRingEntry; RX]>,
+    tx: MaybeUninit<[TxRingEntry; TX]>,
+}
+
+impl<const RX: usize, const TX: usize> DmaRegion<RX, TX> {
+    /// Uninitialized storage, usable as initializer of a `static`
+    pub const fn new() -> Self {
+        DmaRegion {
+            rx: MaybeUninit::uninit(),
+            tx: MaybeUninit::uninit(),
+        }
+    }
+
+    /// Initialize all entries and hand out the rings
+    ///
+    /// Entries are written one by one, so that the rings are not
+    /// built up on the stack first. Calling this again resets them.
+    pub fn split(&mut self) -> (&mut [RxRingEntry; RX], &mut [TxRingEntry; TX]) {
+        let rx = self.rx.as_mut_ptr() as *mut RxRingEntry;
+        for i in 0..RX {
+            unsafe { rx.add(i).write(RxRingEntry::new()) };
+        }
+        let tx = self.tx.as_mut_ptr() as *mut TxRingEntry;
+        for i in 0..TX {
+            unsafe { tx.add(i).write(TxRingEntry::new()) };
+        }
+
+        // All entries have been initialized above
+        unsafe { (&mut *self.rx.as_mut_ptr(), &mut *self.tx.as_mut_ptr()) }
+    }
+}
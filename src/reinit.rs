@@ -0,0 +1,88 @@
+//! Recovery of a wedged MAC without reconstructing `Eth`
+
+use crate::{Eth, InitError};
+
+/// Fast Ethernet speed, duplex mode, and retry disable, which
+/// `init()` derives from the config or the PHY
+const MACCR_FES: u32 = 1 << 14;
+const MACCR_DM: u32 = 1 << 11;
+const MACCR_RD: u32 = 1 << 9;
+const MACCR_LINK_MASK: u32 = MACCR_FES | MACCR_DM | MACCR_RD;
+
+/// Registers that are configured through `Eth` methods after
+/// initialization, and would be lost by a MAC reset
+struct SavedRegisters {
+    maccr: u32,
+    macffr: u32,
+    machthr: u32,
+    machtlr: u32,
+    /// High and low register of each of the four MAC addresses
+    addresses: [(u32, u32); 4],
+    dmaier: u32,
+}
+
+impl<'rx, 'tx> Eth<'rx, 'tx> {
+    /// Reset and reinitialize the MAC and DMA in place, e.g. when
+    /// `mac_debug()` has shown the MAC to be stuck
+    ///
+    /// Stops the DMA engines, resets the MAC, initializes it again
+    /// with the current [`Config`](struct.Config.html), and restarts
+    /// both rings. The MAC addresses, the frame filter, the enabled
+    /// interrupts, and the MAC settings changed since initialization
+    /// are preserved. Speed and duplex mode are taken from the
+    /// config, or from the PHY if auto-negotiation completes.
+    ///
+    /// As with [`start()`](#method.start), received packets that
+    /// have not been consumed, and packets that were queued for
+    /// sending, are discarded.
+    pub fn reinit(&mut self) -> Result<(), InitError> {
+        // A DMA engine that does not stop is reset along with the
+        // MAC below, which is what reinit() is for
+        let _ = self.stop();
+        let saved = self.save_registers();
+        self.init(None)?;
+        self.restore_registers(&saved);
+        self.start();
+        Ok(())
+    }
+
+    fn save_registers(&self) -> SavedRegisters {
+        let mac = &self.eth_mac;
+        SavedRegisters {
+            maccr: mac.maccr.read().bits(),
+            macffr: mac.macffr.read().bits(),
+            machthr: mac.machthr.read().bits(),
+            machtlr: mac.machtlr.read().bits(),
+            addresses: [
+                (mac.maca0hr.read().bits(), mac.maca0lr.read().bits()),
+                (mac.maca1hr.read().bits(), mac.maca1lr.read().bits()),
+                (mac.maca2hr.read().bits(), mac.maca2lr.read().bits()),
+                (mac.maca3hr.read().bits(), mac.maca3lr.read().bits()),
+            ],
+            dmaier: self.eth_dma.dmaier.read().bits(),
+        }
+    }
+
+    fn restore_registers(&self, saved: &SavedRegisters) {
+        let mac = &self.eth_mac;
+        mac.maccr.modify(|r, w| unsafe {
+            w.bits((saved.maccr & !MACCR_LINK_MASK) | (r.bits() & MACCR_LINK_MASK))
+        });
+        mac.macffr.write(|w| unsafe { w.bits(saved.macffr) });
+        mac.machthr.write(|w| unsafe { w.bits(saved.machthr) });
+        mac.machtlr.write(|w| unsafe { w.bits(saved.machtlr) });
+        // Each address is updated once its low register is written
+        let [a0, a1, a2, a3] = saved.addresses;
+        mac.maca0hr.write(|w| unsafe { w.bits(a0.0) });
+        mac.maca0lr.write(|w| unsafe { w.bits(a0.1) });
+        mac.maca1hr.write(|w| unsafe { w.bits(a1.0) });
+        mac.maca1lr.write(|w| unsafe { w.bits(a1.1) });
+        mac.maca2hr.write(|w| unsafe { w.bits(a2.0) });
+        mac.maca2lr.write(|w| unsafe { w.bits(a2.1) });
+        mac.maca3hr.write(|w| unsafe { w.bits(a3.0) });
+        mac.maca3lr.write(|w| unsafe { w.bits(a3.1) });
+        self.eth_dma
+            .dmaier
+            .write(|w| unsafe { w.bits(saved.dmaier) });
+    }
+}
@@ -1,15 +1,54 @@
 use aligned::{Aligned, A8};
 use core::ops::{Deref, DerefMut};
 
-use crate::MTU;
+use crate::{InitError, MTU};
 
+/// Core-Coupled Memory, which is not connected to the DMA bus matrix
+#[cfg(feature = "stm32f4xx")]
+const CCM: core::ops::Range<usize> = 0x1000_0000..0x1001_0000;
+
+/// The DMA engine only accesses whole words of descriptors
+const DESCRIPTOR_ALIGN: usize = 4;
+
+/// State of a DMA engine, as reported in `dmasr`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunningState {
+    /// Reset, or stopped by command
+    Stopped,
+    /// Fetching or closing a descriptor, or transferring data
+    Running,
+    /// Waiting for a frame to arrive (Rx only)
+    Waiting,
+    /// The next descriptor is not owned by the DMA engine, or a
+    /// transmit underflow occurred. A poll demand resumes it.
+    Suspended,
+    /// Reserved value
+    Unknown,
+}
+
+impl RunningState {
+    /// Running or waiting for a frame?
+    pub fn is_running(&self) -> bool {
+        *self == RunningState::Running || *self == RunningState::Waiting
+    }
+}
+
+/// Descriptors are used in chained mode: the second buffer address
+/// points to the next descriptor.
 pub trait RingDescriptor {
-    fn setup(&mut self, buffer: *const u8, len: usize, next: Option<&Self>);
+    fn setup(&mut self, buffer: *const u8, len: usize, next: *const Self, end_of_ring: bool);
+    /// Next descriptor, or `None` if chaining is not enabled
+    fn next_descriptor(&self) -> Option<u32>;
+    fn is_end_of_ring(&self) -> bool;
 }
 
 pub struct RingEntry<T: Clone + RingDescriptor> {
     desc: Aligned<A8, [T; 1]>,
     buffer: Aligned<A8, [u8; MTU]>,
+    /// Address of the next descriptor in the chain, kept outside the
+    /// descriptor so that it can be restored after the DMA engine
+    /// has overwritten it with a timestamp
+    next: u32,
 }
 
 impl<T: Clone + RingDescriptor> Clone for RingEntry<T> {
@@ -17,6 +56,7 @@ impl<T: Clone + RingDescriptor> Clone for RingEntry<T> {
         RingEntry {
             desc: Aligned((*self.desc).clone()),
             buffer: Aligned((*self.buffer).clone()),
+            next: self.next,
         }
     }
 }
@@ -32,14 +72,40 @@ impl<T: Clone + RingDescriptor + Default> RingEntry<T> {
         RingEntry {
             desc: Aligned([T::default()]),
             buffer: Aligned([0; MTU]),
+            next: 0,
+        }
+    }
+
+    /// Check that the DMA engine can use the descriptor and buffer
+    pub(crate) fn validate(&self) -> Result<(), InitError> {
+        let desc = self.desc() as *const T as usize;
+        let buffer = self.buffer.as_ptr() as usize;
+
+        if desc % DESCRIPTOR_ALIGN != 0 || buffer % DESCRIPTOR_ALIGN != 0 {
+            return Err(InitError::MisalignedBuffer);
+        }
+        #[cfg(feature = "stm32f4xx")]
+        {
+            let buffer_end = buffer + self.buffer.len();
+            if CCM.contains(&desc) || CCM.contains(&buffer) || CCM.contains(&(buffer_end - 1)) {
+                return Err(InitError::InaccessibleBuffer);
+            }
         }
+
+        Ok(())
     }
 
-    pub(crate) fn setup(&mut self, next: Option<&Self>) {
+    pub(crate) fn setup(&mut self, next: *const T, end_of_ring: bool) {
         let buffer = self.buffer.as_ptr();
         let len = self.buffer.len();
-        self.desc_mut()
-            .setup(buffer, len, next.map(|next| next.desc()));
+        self.next = next as u32;
+        self.desc_mut().setup(buffer, len, next, end_of_ring);
+    }
+
+    /// Address of the next descriptor, as set up by `setup_chain()`
+    #[inline]
+    pub(crate) fn next(&self) -> u32 {
+        self.next
     }
 
     #[inline]
@@ -62,3 +128,89 @@ impl<T: Clone + RingDescriptor + Default> RingEntry<T> {
         &mut (*self.buffer)[..]
     }
 }
+
+/// Link all descriptors into a chain
+///
+/// The last descriptor has the end-of-ring flag set and also points
+/// back to the first one, so the DMA engine wraps around to the start
+/// of `entries` instead of reading past its end.
+pub(crate) fn setup_chain<T: Clone + RingDescriptor + Default>(entries: &mut [RingEntry<T>]) {
+    let first = entries[0].desc() as *const T;
+    let len = entries.len();
+    for i in 0..len {
+        let end_of_ring = i + 1 == len;
+        let next = if end_of_ring {
+            first
+        } else {
+            entries[i + 1].desc() as *const T
+        };
+        entries[i].setup(next, end_of_ring);
+    }
+    debug_assert!(chain_is_valid(entries));
+}
+
+/// Does every descriptor point to its successor, with only the last
+/// one marked as end of ring?
+fn chain_is_valid<T: Clone + RingDescriptor + Default>(entries: &[RingEntry<T>]) -> bool {
+    let len = entries.len();
+    entries.iter().enumerate().all(|(i, entry)| {
+        let expected = entries[(i + 1) % len].desc() as *const T as u32;
+        entry.desc().next_descriptor() == Some(expected)
+            && entry.desc().is_end_of_ring() == (i + 1 == len)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::setup_chain;
+    use crate::{RxRing, RxRingEntry, TxRing, TxRingEntry};
+
+    /// RDES1: second address chained
+    const RDES1_RCH: u32 = 1 << 14;
+    /// RDES1: receive end of ring
+    const RDES1_RER: u32 = 1 << 15;
+    /// TDES0: second address chained
+    const TDES0_TCH: u32 = 1 << 20;
+    /// TDES0: transmit end of ring
+    const TDES0_TER: u32 = 1 << 21;
+
+    #[test]
+    fn rx_chain_wraps_to_first_descriptor() {
+        let mut entries = [RxRingEntry::new(), RxRingEntry::new(), RxRingEntry::new()];
+        setup_chain(&mut entries);
+        let addresses: [u32; 3] = [
+            entries[0].desc() as *const _ as u32,
+            entries[1].desc() as *const _ as u32,
+            entries[2].desc() as *const _ as u32,
+        ];
+        let ring = RxRing::new(&mut entries);
+
+        for i in 0..3 {
+            let next = addresses[(i + 1) % 3];
+            let words = ring.descriptor_words(i);
+            assert_eq!(words[3], next, "RDES3 of entry {}", i);
+            assert_eq!(words[1] & RDES1_RCH, RDES1_RCH, "RCH of entry {}", i);
+            assert_eq!(words[1] & RDES1_RER != 0, i == 2, "RER of entry {}", i);
+        }
+    }
+
+    #[test]
+    fn tx_chain_wraps_to_first_descriptor() {
+        let mut entries = [TxRingEntry::new(), TxRingEntry::new(), TxRingEntry::new()];
+        setup_chain(&mut entries);
+        let addresses: [u32; 3] = [
+            entries[0].desc() as *const _ as u32,
+            entries[1].desc() as *const _ as u32,
+            entries[2].desc() as *const _ as u32,
+        ];
+        let ring = TxRing::new(&mut entries);
+
+        for i in 0..3 {
+            let next = addresses[(i + 1) % 3];
+            let words = ring.descriptor_words(i);
+            assert_eq!(words[3], next, "TDES3 of entry {}", i);
+            assert_eq!(words[0] & TDES0_TCH, TDES0_TCH, "TCH of entry {}", i);
+            assert_eq!(words[0] & TDES0_TER != 0, i == 2, "TER of entry {}", i);
+        }
+    }
+}
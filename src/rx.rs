@@ -1,5 +1,7 @@
 use core::default::Default;
+use core::fmt;
 use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{fence, Ordering};
 
 #[cfg(feature = "stm32f107")]
 use stm32f1::stm32f107::ETHERNET_DMA;
@@ -8,14 +10,20 @@ use stm32f4xx_hal::stm32::ETHERNET_DMA;
 
 use crate::{
     desc::Descriptor,
-    ring::{RingDescriptor, RingEntry},
+    ring::{setup_chain, RingDescriptor, RingEntry, RunningState},
 };
 
 #[derive(Debug, PartialEq)]
 pub enum RxError {
+    /// No frame has been received. This is not a failure, try again
+    /// later.
     WouldBlock,
+    /// The frame did not fit into a single descriptor
     Truncated,
+    /// The DMA engine reported an error for the frame
     DmaError,
+    /// The destination buffer is shorter than the received frame
+    BufferTooSmall,
 }
 
 /// Owned by DMA engine
@@ -26,14 +34,34 @@ const RXDESC_0_FS: u32 = 1 << 9;
 const RXDESC_0_LS: u32 = 1 << 8;
 /// Error summary
 const RXDESC_0_ES: u32 = 1 << 15;
+/// CRC error
+const RXDESC_0_CE: u32 = 1 << 1;
+/// Errors that are summarized by ES: descriptor error, overflow,
+/// IP header checksum error or giant frame, late collision, watchdog
+/// timeout, receive error, and CRC error
+const RXDESC_0_ERRORS: u32 =
+    (1 << 14) | (1 << 11) | (1 << 7) | (1 << 6) | (1 << 4) | (1 << 3) | RXDESC_0_CE;
 /// Frame length
 const RXDESC_0_FL_MASK: u32 = 0x3FFF;
 const RXDESC_0_FL_SHIFT: usize = 16;
+/// VLAN tagged frame
+const RXDESC_0_VLAN: u32 = 1 << 10;
+/// IP header checksum error, with checksum offload
+const RXDESC_0_IPHCE: u32 = 1 << 7;
+/// Timestamp valid: the same bit as IPHCE, while timestamping is
+/// enabled
+const RXDESC_0_TSV: u32 = 1 << 7;
+/// Frame type, with checksum offload: IPv4 or IPv6
+const RXDESC_0_FT: u32 = 1 << 5;
+/// Payload checksum error, with checksum offload
+const RXDESC_0_PCE: u32 = 1 << 0;
 
 const RXDESC_1_RBS_SHIFT: usize = 0;
 const RXDESC_1_RBS_MASK: u32 = 0x0fff << RXDESC_1_RBS_SHIFT;
 /// Second address chained
 const RXDESC_1_RCH: u32 = 1 << 14;
+/// Disable interrupt on completion
+const RXDESC_1_DIC: u32 = 1 << 31;
 /// End Of Ring
 const RXDESC_1_RER: u32 = 1 << 15;
 
@@ -59,9 +87,22 @@ impl RxDescriptor {
         (self.desc.read(0) & RXDESC_0_OWN) == RXDESC_0_OWN
     }
 
+    /// Restore the buffer addresses, which the DMA engine replaces
+    /// with the frame timestamp when timestamping is enabled
+    fn restore_buffers(&mut self, buffer1: u32, buffer2: u32) {
+        unsafe {
+            self.desc.write(2, buffer1);
+            self.desc.write(3, buffer2);
+        }
+    }
+
     /// Pass ownership to the DMA engine
     fn set_owned(&mut self) {
         unsafe {
+            // The DMA engine may use the descriptor as soon as it sees
+            // OWN, so all writes above, and software's reads of the
+            // buffer, must be complete before it is set.
+            fence(Ordering::Release);
             self.desc.modify(0, |w| w | RXDESC_0_OWN);
         }
     }
@@ -70,6 +111,11 @@ impl RxDescriptor {
         (self.desc.read(0) & RXDESC_0_ES) == RXDESC_0_ES
     }
 
+    /// Is a CRC error the only error of the frame?
+    fn has_only_crc_error(&self) -> bool {
+        (self.desc.read(0) & RXDESC_0_ERRORS) == RXDESC_0_CE
+    }
+
     /// Descriptor contains first buffer of frame
     fn is_first(&self) -> bool {
         (self.desc.read(0) & RXDESC_0_FS) == RXDESC_0_FS
@@ -96,62 +142,231 @@ impl RxDescriptor {
         }
     }
 
-    fn set_end_of_ring(&mut self) {
+    fn set_end_of_ring(&mut self, end_of_ring: bool) {
         unsafe {
-            self.desc.modify(1, |w| w | RXDESC_1_RER);
+            if end_of_ring {
+                self.desc.modify(1, |w| w | RXDESC_1_RER);
+            } else {
+                self.desc.modify(1, |w| w & !RXDESC_1_RER);
+            }
+        }
+    }
+
+    fn set_interrupt_on_completion(&mut self, enabled: bool) {
+        unsafe {
+            if enabled {
+                self.desc.modify(1, |w| w & !RXDESC_1_DIC);
+            } else {
+                self.desc.modify(1, |w| w | RXDESC_1_DIC);
+            }
         }
     }
 
     fn get_frame_len(&self) -> usize {
         ((self.desc.read(0) >> RXDESC_0_FL_SHIFT) & RXDESC_0_FL_MASK) as usize
     }
+
+    /// Timestamp that the DMA engine has written in place of the
+    /// buffer addresses, if any
+    fn get_timestamp(&self) -> Option<u64> {
+        if (self.desc.read(0) & RXDESC_0_TSV) != RXDESC_0_TSV {
+            return None;
+        }
+        let low = self.desc.read(2);
+        let high = self.desc.read(3);
+        // All ones indicate a corrupted timestamp
+        if low == !0 && high == !0 {
+            None
+        } else {
+            Some((u64::from(high) << 32) | u64::from(low))
+        }
+    }
 }
 
 pub type RxRingEntry = RingEntry<RxDescriptor>;
 
 impl RingDescriptor for RxDescriptor {
-    fn setup(&mut self, buffer: *const u8, len: usize, next: Option<&Self>) {
+    fn setup(&mut self, buffer: *const u8, len: usize, next: *const Self, end_of_ring: bool) {
         self.set_buffer1(buffer, len);
-        match next {
-            Some(next) => self.set_buffer2(&next.desc as *const Descriptor as *const u8),
-            None => {
-                self.set_buffer2(0 as *const u8);
-                self.set_end_of_ring();
-            }
-        };
+        self.set_buffer2(next as *const u8);
+        self.set_end_of_ring(end_of_ring);
         self.set_owned();
     }
+
+    fn next_descriptor(&self) -> Option<u32> {
+        if (self.desc.read(1) & RXDESC_1_RCH) == RXDESC_1_RCH {
+            Some(self.desc.read(3))
+        } else {
+            None
+        }
+    }
+
+    fn is_end_of_ring(&self) -> bool {
+        (self.desc.read(1) & RXDESC_1_RER) == RXDESC_1_RER
+    }
 }
 
 impl RxRingEntry {
-    fn take_received(&mut self) -> Result<RxPacket, RxError> {
-        match self.desc().is_owned() {
-            true => Err(RxError::WouldBlock),
-            false if self.desc().has_error() => {
-                self.desc_mut().set_owned();
-                Err(RxError::DmaError)
+    /// Restore the buffer addresses and pass ownership to the DMA
+    /// engine
+    fn set_owned(&mut self) {
+        let buffer1 = self.as_slice().as_ptr() as u32;
+        let buffer2 = self.next();
+        let desc = self.desc_mut();
+        desc.restore_buffers(buffer1, buffer2);
+        desc.set_owned();
+    }
+
+    fn take_received<'a>(
+        &'a mut self,
+        eth_dma: &'a ETHERNET_DMA,
+        timestamps: bool,
+        checksum_offload: bool,
+        crc_stripping: bool,
+        software_checksums: bool,
+        crc_errors: bool,
+    ) -> Result<RxPacket<'a>, RxError> {
+        if self.desc().is_owned() {
+            return Err(RxError::WouldBlock);
+        }
+        // The DMA engine writes the frame and the status before it
+        // clears OWN. None of the reads below may happen before OWN
+        // has been seen clear, or they could return a half-written
+        // frame. This compiles to a DMB.
+        fence(Ordering::Acquire);
+
+        let crc_error = self.desc().has_error() && self.desc().has_only_crc_error();
+        if self.desc().has_error() && !(crc_errors && crc_error) {
+            self.set_owned();
+            Err(RxError::DmaError)
+        } else if self.desc().is_first() && self.desc().is_last() {
+            let mut frame_len = self.desc().get_frame_len();
+            if crc_stripping {
+                frame_len -= fcs_len(&self.as_slice()[..frame_len]);
             }
-            false if self.desc().is_first() && self.desc().is_last() => {
-                let frame_len = self.desc().get_frame_len();
-                // TODO: obtain ethernet frame type (RDESC_1_FT)
-
-                let pkt = RxPacket {
-                    entry: self,
-                    length: frame_len,
-                };
-                Ok(pkt)
+            // TODO: obtain ethernet frame type (RDESC_1_FT)
+            let timestamp = if timestamps {
+                self.desc().get_timestamp()
+            } else {
+                None
+            };
+
+            let mut checksums = if checksum_offload {
+                checksum_results(self.desc().desc.read(0))
+            } else {
+                (ChecksumResult::NotChecked, ChecksumResult::NotChecked)
+            };
+            if software_checksums {
+                checksums = fill_in_checksums(checksums, &self.as_slice()[..frame_len]);
             }
-            false => {
-                self.desc_mut().set_owned();
-                Err(RxError::Truncated)
+
+            let pkt = RxPacket {
+                entry: self,
+                eth_dma,
+                length: frame_len,
+                timestamp,
+                checksums,
+                crc_error,
+            };
+            Ok(pkt)
+        } else {
+            self.set_owned();
+            Err(RxError::Truncated)
+        }
+    }
+}
+
+/// Length of the FCS that the MAC has left at the end of `frame`
+///
+/// Automatic pad/CRC stripping (`apcs`) only covers IEEE 802.3
+/// length frames. On STM32F4xx, `cstf` covers Ethernet II type
+/// frames, but the STM32F107 has no such setting.
+#[allow(unused_variables)]
+fn fcs_len(frame: &[u8]) -> usize {
+    #[cfg(feature = "stm32f107")]
+    {
+        const ETHERTYPE_MIN: u16 = 0x0600;
+        const FCS_LEN: usize = 4;
+        if let Some(&[hi, lo]) = frame.get(12..14) {
+            if u16::from_be_bytes([hi, lo]) >= ETHERTYPE_MIN && frame.len() >= 14 + FCS_LEN {
+                return FCS_LEN;
             }
         }
     }
+    0
+}
+
+/// Outcome of the hardware checksum verification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumResult {
+    /// The MAC did not verify the checksum, e.g. because checksum
+    /// offload is disabled or the frame is not IP, fragmented, or
+    /// carries an unsupported protocol. Software must verify it.
+    NotChecked,
+    Valid,
+    Invalid,
+}
+
+/// Decode the IP header and payload checksum results from RDES0
+fn checksum_results(rdes0: u32) -> (ChecksumResult, ChecksumResult) {
+    use ChecksumResult::*;
+
+    let ft = (rdes0 & RXDESC_0_FT) == RXDESC_0_FT;
+    let iphce = (rdes0 & RXDESC_0_IPHCE) == RXDESC_0_IPHCE;
+    let pce = (rdes0 & RXDESC_0_PCE) == RXDESC_0_PCE;
+    match (ft, iphce, pce) {
+        // IPv4 or IPv6, no errors
+        (true, false, false) => (Valid, Valid),
+        // Payload checksum error
+        (true, false, true) => (Valid, Invalid),
+        // IP header checksum error
+        (true, true, false) => (Invalid, NotChecked),
+        // Both errors
+        (true, true, true) => (Invalid, Invalid),
+        // IP frame with unsupported payload
+        (false, false, true) => (Valid, NotChecked),
+        // IEEE 802.3 length frame, non-IP type frame, or reserved
+        _ => (NotChecked, NotChecked),
+    }
+}
+
+/// Replace the results that the MAC left `NotChecked` with a
+/// software verification of `frame`
+///
+/// Frames that the MAC has checked completely are not touched.
+fn fill_in_checksums(
+    hardware: (ChecksumResult, ChecksumResult),
+    frame: &[u8],
+) -> (ChecksumResult, ChecksumResult) {
+    use ChecksumResult::NotChecked;
+
+    if hardware.0 != NotChecked && hardware.1 != NotChecked {
+        return hardware;
+    }
+    let software = crate::checksum::verify(frame);
+    let pick = |hardware: ChecksumResult, software| match hardware {
+        NotChecked => software,
+        checked => checked,
+    };
+    (pick(hardware.0, software.0), pick(hardware.1, software.1))
+}
+
+/// How the destination address of a received frame is addressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameType {
+    Unicast,
+    Multicast,
+    Broadcast,
 }
 
 pub struct RxPacket<'a> {
     entry: &'a mut RxRingEntry,
+    eth_dma: &'a ETHERNET_DMA,
     length: usize,
+    timestamp: Option<u64>,
+    /// IP header and payload checksum results
+    checksums: (ChecksumResult, ChecksumResult),
+    crc_error: bool,
 }
 
 impl<'a> Deref for RxPacket<'a> {
@@ -168,23 +383,135 @@ impl<'a> DerefMut for RxPacket<'a> {
     }
 }
 
+impl<'a> fmt::Debug for RxPacket<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RxPacket")
+            .field("length", &self.length)
+            .field("timestamp", &self.timestamp)
+            .field("ip_header_checksum", &self.checksums.0)
+            .field("payload_checksum", &self.checksums.1)
+            .finish()
+    }
+}
+
 impl<'a> Drop for RxPacket<'a> {
     fn drop(&mut self) {
-        self.entry.desc_mut().set_owned();
+        self.entry.set_owned();
+        // The DMA engine suspends on a full ring. With this
+        // descriptor handed back it can go on receiving right away.
+        if running_state(self.eth_dma) == RunningState::Suspended {
+            demand_poll(self.eth_dma);
+        }
     }
 }
 
 impl<'a> RxPacket<'a> {
-    // Pass back to DMA engine
+    /// Hand the descriptor back to the DMA engine right away
+    ///
+    /// Dropping the packet does the same, but only at the end of its
+    /// scope. Call this as soon as the data has been consumed, so
+    /// that the descriptor can receive the next frame during a long
+    /// handler:
+    ///
+    /// ```ignore
+    /// let pkt = eth.recv_next()?;
+    /// let header = parse_header(&pkt);
+    /// pkt.free();
+    /// handle(header);
+    /// ```
+    ///
+    /// If the Rx DMA has suspended because the ring was full, it is
+    /// asked to poll again and resumes with this descriptor. The
+    /// same happens when an `RxPacket` is dropped.
     pub fn free(self) {
         drop(self)
     }
+
+    /// Time of arrival, if it was captured
+    ///
+    /// Seconds are in the upper 32 bits, subseconds in the lower 32
+    /// bits. Always `None` unless enabled with
+    /// [`Eth::set_rx_timestamps()`](../struct.Eth.html#method.set_rx_timestamps).
+    pub fn timestamp(&self) -> Option<u64> {
+        self.timestamp
+    }
+
+    /// Length of the frame, excluding the FCS unless CRC stripping
+    /// has been disabled with
+    /// [`Eth::set_crc_stripping()`](../struct.Eth.html#method.set_crc_stripping)
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Result of the IPv4 header checksum verification
+    pub fn ip_header_checksum(&self) -> ChecksumResult {
+        self.checksums.0
+    }
+
+    /// Result of the TCP/UDP/ICMP checksum verification
+    pub fn payload_checksum(&self) -> ChecksumResult {
+        self.checksums.1
+    }
+
+    /// Did the MAC find the FCS to be wrong?
+    ///
+    /// Such frames are only delivered after
+    /// [`Eth::set_crc_error_delivery()`](../struct.Eth.html#method.set_crc_error_delivery).
+    pub fn received_with_crc_error(&self) -> bool {
+        self.crc_error
+    }
+
+    /// Unicast, multicast, or broadcast destination?
+    ///
+    /// The normal Rx descriptors do not record this, so it is
+    /// derived from the destination address.
+    pub fn frame_type(&self) -> FrameType {
+        match self.get(..6) {
+            Some([0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]) => FrameType::Broadcast,
+            Some(dst) if dst[0] & 1 == 1 => FrameType::Multicast,
+            _ => FrameType::Unicast,
+        }
+    }
+
+    /// Is this a MAC control frame, e.g. a pause frame?
+    ///
+    /// These are only delivered as configured with
+    /// [`Eth::set_pass_control_frames()`](../struct.Eth.html#method.set_pass_control_frames).
+    pub fn is_control_frame(&self) -> bool {
+        const ETHERTYPE_MAC_CONTROL: [u8; 2] = [0x88, 0x08];
+        self.get(12..14) == Some(&ETHERTYPE_MAC_CONTROL[..])
+    }
+
+    /// Tag control information of an 802.1Q tagged frame, `None` for
+    /// untagged frames
+    ///
+    /// The VLAN ID is in the lower 12 bits, the priority in the upper
+    /// 3 bits. The MAC flags tagged frames in the descriptor, while
+    /// the tag itself stays in the frame.
+    pub fn vlan_tag(&self) -> Option<u16> {
+        if (self.entry.desc().desc.read(0) & RXDESC_0_VLAN) != RXDESC_0_VLAN {
+            return None;
+        }
+        match self.get(14..16) {
+            Some(&[high, low]) => Some(u16::from_be_bytes([high, low])),
+            _ => None,
+        }
+    }
 }
 
 /// Rx DMA state
 pub struct RxRing<'a> {
     entries: &'a mut [RxRingEntry],
     next_entry: usize,
+    timestamps: bool,
+    checksum_offload: bool,
+    crc_stripping: bool,
+    software_checksums: bool,
+    crc_errors: bool,
 }
 
 impl<'a> RxRing<'a> {
@@ -193,20 +520,18 @@ impl<'a> RxRing<'a> {
         RxRing {
             entries,
             next_entry: 0,
+            timestamps: false,
+            checksum_offload: false,
+            crc_stripping: true,
+            software_checksums: false,
+            crc_errors: false,
         }
     }
 
     /// Setup the DMA engine (**required**)
     pub fn start(&mut self, eth_dma: &ETHERNET_DMA) {
         // Setup ring
-        {
-            let mut previous: Option<&mut RxRingEntry> = None;
-            for entry in self.entries.iter_mut() {
-                previous.map(|previous| previous.setup(Some(entry)));
-                previous = Some(entry);
-            }
-            previous.map(|previous| previous.setup(None));
-        }
+        setup_chain(self.entries);
         self.next_entry = 0;
         let ring_ptr = self.entries[0].desc() as *const RxDescriptor;
         // Register RxDescriptor
@@ -218,40 +543,112 @@ impl<'a> RxRing<'a> {
         self.demand_poll(eth_dma);
     }
 
+    /// Stop the Rx DMA engine once the current frame is transferred
+    pub fn stop(&self, eth_dma: &ETHERNET_DMA) {
+        eth_dma.dmaomr.modify(|_, w| w.sr().clear_bit());
+    }
+
     /// Demand that the DMA engine polls the current `RxDescriptor`
-    /// (when in `RunningState::Stopped`.)
+    /// (when in `RunningState::Suspended`.)
     pub fn demand_poll(&self, eth_dma: &ETHERNET_DMA) {
-        eth_dma.dmarpdr.write(|w| unsafe { w.rpd().bits(1) });
+        demand_poll(eth_dma);
     }
 
     /// Get current `RunningState`
     pub fn running_state(&self, eth_dma: &ETHERNET_DMA) -> RunningState {
-        match eth_dma.dmasr.read().rps().bits() {
-            //  Reset or Stop Receive Command issued
-            0b000 => RunningState::Stopped,
-            //  Fetching receive transfer descriptor
-            0b001 => RunningState::Running,
-            //  Waiting for receive packet
-            0b011 => RunningState::Running,
-            //  Receive descriptor unavailable
-            0b100 => RunningState::Stopped,
-            //  Closing receive descriptor
-            0b101 => RunningState::Running,
-            //  Transferring the receive packet data from receive buffer to host memory
-            0b111 => RunningState::Running,
-            _ => RunningState::Unknown,
+        running_state(eth_dma)
+    }
+
+    /// Read frame timestamps from the descriptors
+    pub fn set_timestamps(&mut self, enabled: bool) {
+        self.timestamps = enabled;
+    }
+
+    /// Read checksum results from the descriptors
+    pub fn set_checksum_offload(&mut self, enabled: bool) {
+        self.checksum_offload = enabled;
+    }
+
+    /// Remove an FCS that the MAC has not stripped from the frames
+    pub fn set_crc_stripping(&mut self, enabled: bool) {
+        self.crc_stripping = enabled;
+    }
+
+    /// Verify the checksums that the MAC did not check in software
+    pub fn set_software_checksums(&mut self, enabled: bool) {
+        self.software_checksums = enabled;
+    }
+
+    /// Return frames whose only error is a CRC error as packets
+    /// instead of `RxError::DmaError`
+    pub fn set_crc_errors(&mut self, enabled: bool) {
+        self.crc_errors = enabled;
+    }
+
+    /// Are frames with a CRC error returned as packets?
+    pub(crate) fn crc_errors(&self) -> bool {
+        self.crc_errors
+    }
+
+    /// Whether the DMA engine signals completion of each frame
+    /// immediately, or leaves it to the receive watchdog
+    pub fn set_interrupt_on_completion(&mut self, enabled: bool) {
+        for entry in self.entries.iter_mut() {
+            entry.desc_mut().set_interrupt_on_completion(enabled);
         }
     }
 
+    /// Raw words of the descriptor at `index`
+    #[cfg(any(test, feature = "debug-descriptors"))]
+    pub fn descriptor_words(&self, index: usize) -> [u32; 4] {
+        self.entries[index].desc().desc.words()
+    }
+
+    /// Index of the entry that the DMA engine is currently working
+    /// on
+    pub fn current_entry(&self, eth_dma: &ETHERNET_DMA) -> Option<usize> {
+        let addr = eth_dma.dmachrdr.read().bits() as usize;
+        self.entries
+            .iter()
+            .position(|entry| entry.desc() as *const RxDescriptor as usize == addr)
+    }
+
+    /// Number of entries in the ring
+    pub(crate) fn entries_len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Has the DMA engine handed the next entry back to us?
+    pub fn next_entry_ready(&self) -> bool {
+        !self.entries[self.next_entry].desc().is_owned()
+    }
+
+    /// Number of entries that the DMA engine has handed back, in
+    /// the order `recv_next()` takes them
+    pub fn pending(&self) -> usize {
+        let entries_len = self.entries.len();
+        (0..entries_len)
+            .map(|i| (self.next_entry + i) % entries_len)
+            .take_while(|&index| !self.entries[index].desc().is_owned())
+            .count()
+    }
+
     /// Receive the next packet (if any is ready), or return `None`
     /// immediately.
-    pub fn recv_next(&mut self, eth_dma: &ETHERNET_DMA) -> Result<RxPacket, RxError> {
+    pub fn recv_next<'b>(&'b mut self, eth_dma: &'b ETHERNET_DMA) -> Result<RxPacket<'b>, RxError> {
         if !self.running_state(eth_dma).is_running() {
             self.demand_poll(eth_dma);
         }
 
         let entries_len = self.entries.len();
-        let result = self.entries[self.next_entry].take_received();
+        let result = self.entries[self.next_entry].take_received(
+            eth_dma,
+            self.timestamps,
+            self.checksum_offload,
+            self.crc_stripping,
+            self.software_checksums,
+            self.crc_errors,
+        );
         match result {
             Err(RxError::WouldBlock) => {}
             _ => {
@@ -266,17 +663,28 @@ impl<'a> RxRing<'a> {
     }
 }
 
-/// Running state of the `RxRing`
-#[derive(PartialEq, Eq, Debug)]
-pub enum RunningState {
-    Unknown,
-    Stopped,
-    Running,
+/// Demand that the DMA engine polls the current `RxDescriptor`
+fn demand_poll(eth_dma: &ETHERNET_DMA) {
+    // Make sure the OWN bit is visible before the DMA engine
+    // starts polling
+    fence(Ordering::SeqCst);
+    eth_dma.dmarpdr.write(|w| unsafe { w.rpd().bits(1) });
 }
 
-impl RunningState {
-    /// whether self equals to `RunningState::Running`
-    pub fn is_running(&self) -> bool {
-        *self == RunningState::Running
+fn running_state(eth_dma: &ETHERNET_DMA) -> RunningState {
+    match eth_dma.dmasr.read().rps().bits() {
+        //  Reset or Stop Receive Command issued
+        0b000 => RunningState::Stopped,
+        //  Fetching receive transfer descriptor
+        0b001 => RunningState::Running,
+        //  Waiting for receive packet
+        0b011 => RunningState::Waiting,
+        //  Receive descriptor unavailable
+        0b100 => RunningState::Suspended,
+        //  Closing receive descriptor
+        0b101 => RunningState::Running,
+        //  Transferring the receive packet data from receive buffer to host memory
+        0b111 => RunningState::Running,
+        _ => RunningState::Unknown,
     }
 }
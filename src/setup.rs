@@ -9,21 +9,57 @@ use stm32f4xx_hal::gpio::{
     gpiob::PB13,
     gpioc::{PC1, PC4, PC5},
     gpiog::{PG11, PG13},
-    Speed::VeryHigh,
 };
 
+use embedded_hal::blocking::delay::DelayUs;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{phy, PHY_RESET_SETTLE_US};
+
+/// How the MAC is connected to the PHY
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaInterface {
+    /// Media Independent Interface
+    Mii,
+    /// Reduced Media Independent Interface
+    Rmii,
+}
+
+impl MediaInterface {
+    /// Value of the `mii_rmii_sel` bit
+    fn is_rmii(self) -> bool {
+        self == MediaInterface::Rmii
+    }
+}
+
+/// Panic if the PHY selected via feature cannot use `interface`
+fn check_interface(interface: MediaInterface) {
+    assert!(
+        interface.is_rmii() || phy::SUPPORTS_MII,
+        "The selected PHY cannot be connected via MII"
+    );
+}
+
 /// Initialize GPIO pins. Enable syscfg and ethernet clocks. Reset the
 /// Ethernet MAC.
 ///
+/// `interface` must match the board's wiring, otherwise no frames
+/// are transferred at all. Panics if the PHY selected via feature
+/// does not support `interface`.
+///
 /// If supported, you should also call `setup_pins()`.
 #[cfg(feature = "stm32f4xx")]
-pub fn setup(rcc: &RCC, syscfg: &SYSCFG) {
+pub fn setup(rcc: &RCC, syscfg: &SYSCFG, interface: MediaInterface) {
+    check_interface(interface);
+
     // enable syscfg clock
     rcc.apb2enr.modify(|_, w| w.syscfgen().set_bit());
 
     // select MII or RMII mode
     // 0 = MII, 1 = RMII
-    syscfg.pmc.modify(|_, w| w.mii_rmii_sel().set_bit());
+    syscfg
+        .pmc
+        .modify(|_, w| w.mii_rmii_sel().bit(interface.is_rmii()));
 
     // enable ethernet clocks
     rcc.ahb1enr.modify(|_, w| {
@@ -38,10 +74,13 @@ pub fn setup(rcc: &RCC, syscfg: &SYSCFG) {
     reset_pulse(&rcc);
 }
 #[cfg(feature = "stm32f107")]
-pub fn setup(rcc: &RCC, afio: &AFIO) {
+pub fn setup(rcc: &RCC, afio: &AFIO, interface: MediaInterface) {
+    check_interface(interface);
+
     // select MII or RMII mode
     // 0 = MII, 1 = RMII
-    afio.mapr.modify(|_, w| w.mii_rmii_sel().set_bit());
+    afio.mapr
+        .modify(|_, w| w.mii_rmii_sel().bit(interface.is_rmii()));
 
     // enable ethernet clocks
     rcc.ahbenr.modify(|_, w| {
@@ -56,6 +95,23 @@ pub fn setup(rcc: &RCC, afio: &AFIO) {
     reset_pulse(&rcc);
 }
 
+/// Reset the PHY through its hardware reset pin (nRST), which is
+/// active low
+///
+/// Unlike the software reset during [`Eth::new()`](struct.Eth.html#method.new),
+/// this also recovers a PHY that does not respond anymore, e.g.
+/// after a brownout. Call it before `Eth::new()`.
+pub fn reset_phy<P: OutputPin, D: DelayUs<u32>>(
+    reset_pin: &mut P,
+    delay: &mut D,
+) -> Result<(), P::Error> {
+    reset_pin.set_low()?;
+    delay.delay_us(phy::RESET_PULSE_US);
+    reset_pin.set_high()?;
+    delay.delay_us(PHY_RESET_SETTLE_US);
+    Ok(())
+}
+
 #[cfg(feature = "stm32f4xx")]
 fn reset_pulse(rcc: &RCC) {
     rcc.ahb1rstr.modify(|_, w| w.ethmacrst().set_bit());
@@ -70,9 +126,8 @@ fn reset_pulse(rcc: &RCC) {
 /// Pin setup for the **STM32 Nucleo-F429ZI** dev board
 /// (feature: `nucleo-f429zi`)
 ///
-/// Set RMII pins to
-/// * Alternate function 11
-/// * High-speed
+/// See [`pins::setup_rmii_pins()`](pins/fn.setup_rmii_pins.html) for
+/// other boards.
 ///
 /// This function consumes the pins so that you cannot use them
 /// anywhere else by accident.
@@ -88,22 +143,24 @@ pub fn setup_pins<M1, M2, M3, M4, M5, M6, M7, M8, M9>(
     pg11: PG11<M8>,
     pg13: PG13<M9>,
 ) {
-    // PA1 RMII Reference Clock - SB13 ON
-    pa1.into_alternate_af11().set_speed(VeryHigh);
-    // PA2 RMII MDIO - SB160 ON
-    pa2.into_alternate_af11().set_speed(VeryHigh);
-    // PC1 RMII MDC - SB164 ON
-    pc1.into_alternate_af11().set_speed(VeryHigh);
-    // PA7 RMII RX Data Valid D11 JP6 ON
-    pa7.into_alternate_af11().set_speed(VeryHigh);
-    // PC4 RMII RXD0 - SB178 ON
-    pc4.into_alternate_af11().set_speed(VeryHigh);
-    // PC5 RMII RXD1 - SB181 ON
-    pc5.into_alternate_af11().set_speed(VeryHigh);
-    // PG11 RMII TX Enable - SB183 ON
-    pg11.into_alternate_af11().set_speed(VeryHigh);
-    // PG13 RXII TXD0 - SB182 ON
-    pg13.into_alternate_af11().set_speed(VeryHigh);
-    // PB13 RMII TXD1 I2S_A_CK JP7 ON
-    pb13.into_alternate_af11().set_speed(VeryHigh);
+    crate::pins::setup_rmii_pins(
+        // RMII Reference Clock - SB13 ON
+        pa1,
+        // RMII MDIO - SB160 ON
+        pa2,
+        // RMII MDC - SB164 ON
+        pc1,
+        // RMII RX Data Valid D11 JP6 ON
+        pa7,
+        // RMII RXD0 - SB178 ON
+        pc4,
+        // RMII RXD1 - SB181 ON
+        pc5,
+        // RMII TX Enable - SB183 ON
+        pg11,
+        // RXII TXD0 - SB182 ON
+        pg13,
+        // RMII TXD1 I2S_A_CK JP7 ON
+        pb13,
+    );
 }
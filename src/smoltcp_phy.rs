@@ -1,5 +1,5 @@
 use crate::{rx::RxPacket, tx::TxError, Eth};
-use core::intrinsics::transmute;
+use core::mem::transmute;
 use smoltcp::phy::{Device, DeviceCapabilities, RxToken, TxToken};
 use smoltcp::time::Instant;
 use smoltcp::Error;
@@ -10,7 +10,9 @@ impl<'a, 'rx, 'tx, 'b> Device<'a> for &'b mut Eth<'rx, 'tx> {
     type TxToken = EthTxToken<'a>;
 
     fn capabilities(&self) -> DeviceCapabilities {
-        DeviceCapabilities::default()
+        let mut caps = DeviceCapabilities::default();
+        caps.max_transmission_unit = self.mtu();
+        caps
     }
 
     fn receive(&mut self) -> Option<(Self::RxToken, Self::TxToken)> {
@@ -66,7 +68,9 @@ impl<'a> TxToken for EthTxToken<'a> {
     {
         let eth = unsafe { &mut *self.eth };
         match eth.send(len, f) {
-            Err(TxError::WouldBlock) => Err(Error::Exhausted),
+            Err(TxError::FrameTooLarge) => Err(Error::Truncated),
+            // Hardware errors are only reported by `poll_tx_complete()`
+            Err(_) => Err(Error::Exhausted),
             Ok(r) => r,
         }
     }
@@ -0,0 +1,77 @@
+//! Source address insertion and replacement on transmit
+
+use crate::{Eth, TxError, MAX_FRAME_LEN};
+
+/// Length of one MAC address
+const ADDRESS_LEN: usize = 6;
+
+/// What happens to the source address of transmitted frames, see
+/// [`Eth::set_source_address_mode()`](struct.Eth.html#method.set_source_address_mode)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaMode {
+    /// Frames are sent as they are
+    Unchanged,
+    /// Frames are passed without source address, which is inserted
+    /// after the destination address. `length` excludes it.
+    Insert,
+    /// The source address in the frame is overwritten
+    Replace,
+}
+
+impl<'rx, 'tx> Eth<'rx, 'tx> {
+    /// Stamp the station address (see
+    /// [`set_mac_address()`](#method.set_mac_address)) onto frames
+    /// passed to [`send()`](#method.send) and
+    /// [`send_with_checksum()`](#method.send_with_checksum)
+    ///
+    /// The MACs of the supported chips cannot do this themselves, so
+    /// the address is written while the frame is in the Tx buffer.
+    /// This lets software reuse one frame template, whatever address
+    /// it contains. Frames that are too short to hold a source
+    /// address are sent unchanged, at the length they were passed
+    /// with.
+    ///
+    /// Frames sent through [`acquire_tx()`](#method.acquire_tx) or
+    /// from a buffer of the caller are not stamped, and
+    /// [`send_vlan()`](#method.send_vlan) only applies
+    /// `SaMode::Replace`.
+    pub fn set_source_address_mode(&mut self, mode: SaMode) {
+        self.source_address_mode = mode;
+    }
+
+    /// Wrap `f` so that it applies the source address mode, and
+    /// return the resulting frame length along with it
+    pub(crate) fn stamp_source_address<F: FnOnce(&mut [u8]) -> R, R>(
+        &self,
+        length: usize,
+        f: F,
+    ) -> Result<(usize, impl FnOnce(&mut [u8]) -> R), TxError> {
+        let mode = self.source_address_mode;
+        let total = match mode {
+            SaMode::Insert if length >= ADDRESS_LEN => length + ADDRESS_LEN,
+            _ => length,
+        };
+        if total > MAX_FRAME_LEN {
+            return Err(TxError::FrameTooLarge);
+        }
+        let mac = self.mac_address();
+
+        let stamp = move |buf: &mut [u8]| {
+            let r = f(&mut buf[..length]);
+            let source = ADDRESS_LEN..2 * ADDRESS_LEN;
+            match mode {
+                SaMode::Unchanged => {}
+                SaMode::Insert if length >= ADDRESS_LEN => {
+                    buf.copy_within(ADDRESS_LEN..length, source.end);
+                    buf[source].copy_from_slice(mac.as_bytes());
+                }
+                SaMode::Replace if length >= source.end => {
+                    buf[source].copy_from_slice(mac.as_bytes());
+                }
+                _ => {}
+            }
+            r
+        };
+        Ok((total, stamp))
+    }
+}
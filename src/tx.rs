@@ -1,4 +1,5 @@
 use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{fence, Ordering};
 
 #[cfg(feature = "stm32f107")]
 use stm32f1::stm32f107::ETHERNET_DMA;
@@ -7,7 +8,7 @@ use stm32f4xx_hal::stm32::ETHERNET_DMA;
 
 use crate::{
     desc::Descriptor,
-    ring::{RingDescriptor, RingEntry},
+    ring::{setup_chain, RingDescriptor, RingEntry, RunningState},
 };
 
 /// Owned by DMA engine
@@ -24,17 +25,63 @@ const TXDESC_0_TER: u32 = 1 << 21;
 const TXDESC_0_TCH: u32 = 1 << 20;
 /// Error status
 const TXDESC_0_ES: u32 = 1 << 15;
+/// Jabber timeout
+const TXDESC_0_JT: u32 = 1 << 14;
+/// Frame flushed
+const TXDESC_0_FF: u32 = 1 << 13;
+/// Loss of carrier
+const TXDESC_0_LCA: u32 = 1 << 11;
+/// No carrier
+const TXDESC_0_NC: u32 = 1 << 10;
+/// Late collision
+const TXDESC_0_LCO: u32 = 1 << 9;
+/// Excessive collision
+const TXDESC_0_EC: u32 = 1 << 8;
+/// Excessive deferral
+const TXDESC_0_ED: u32 = 1 << 2;
+/// Underflow error
+const TXDESC_0_UF: u32 = 1 << 1;
+/// Disable padding of short frames
+const TXDESC_0_DP: u32 = 1 << 26;
+/// Transmit timestamp enable
+const TXDESC_0_TTSE: u32 = 1 << 25;
+/// Transmit timestamp status
+const TXDESC_0_TTSS: u32 = 1 << 17;
 /// Checksum insertion control
-const TXDESC_0_CIC1: u32 = 1 << 22;
-const TXDESC_0_CIC2: u32 = 1 << 23;
+const TXDESC_0_CIC_SHIFT: u32 = 22;
+const TXDESC_0_CIC_MASK: u32 = 0b11 << TXDESC_0_CIC_SHIFT;
 
 const TXDESC_1_TBS_SHIFT: usize = 0;
 const TXDESC_1_TBS_MASK: u32 = 0x0fff << TXDESC_1_TBS_SHIFT;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TxError {
     /// Ring buffer is full
     WouldBlock,
+    /// Frame does not fit into a descriptor buffer
+    FrameTooLarge,
+    /// Frame is too short to be tagged, see
+    /// [`Eth::send_vlan()`](struct.Eth.html#method.send_vlan)
+    FrameTooShort,
+    /// VLAN ID or priority is out of range
+    InvalidVlanTag,
+    /// The transmitter was active for too long, and the frame was
+    /// cut off
+    JabberTimeout,
+    /// The frame was flushed from the Tx FIFO by software
+    FrameFlushed,
+    /// Carrier was lost during transmission (half-duplex)
+    LossOfCarrier,
+    /// The PHY did not assert carrier (half-duplex)
+    NoCarrier,
+    /// A collision occurred after the collision window (half-duplex)
+    LateCollision,
+    /// Transmission was aborted after 16 collisions (half-duplex)
+    ExcessiveCollision,
+    /// Transmission was deferred for too long (half-duplex)
+    ExcessiveDeferral,
+    /// The DMA engine could not feed the Tx FIFO in time
+    UnderflowError,
 }
 
 #[repr(C)]
@@ -47,15 +94,7 @@ impl Default for TxDescriptor {
     fn default() -> Self {
         let mut desc = Descriptor::default();
         unsafe {
-            desc.write(
-                0,
-                TXDESC_0_TCH
-                    | TXDESC_0_IC
-                    | TXDESC_0_FS
-                    | TXDESC_0_LS
-                    | TXDESC_0_CIC1
-                    | TXDESC_0_CIC2,
-            );
+            desc.write(0, TXDESC_0_TCH | TXDESC_0_IC | TXDESC_0_FS | TXDESC_0_LS);
         }
         TxDescriptor { desc }
     }
@@ -69,16 +108,51 @@ impl TxDescriptor {
 
     /// Pass ownership to the DMA engine
     fn set_owned(&mut self) {
+        // The DMA engine may fetch the descriptor as soon as it sees
+        // OWN. The frame in the buffer and the buffer address and
+        // length written before must have landed in memory by then,
+        // so this compiles to a DMB before the OWN bit is written.
+        fence(Ordering::Release);
         unsafe {
             self.desc.modify(0, |w| w | TXDESC_0_OWN);
         }
     }
 
-    #[allow(unused)]
+    /// Take back ownership from the stopped DMA engine
+    fn clear_owned(&mut self) {
+        unsafe {
+            self.desc.modify(0, |w| w & !TXDESC_0_OWN);
+        }
+    }
+
     fn has_error(&self) -> bool {
         (self.desc.read(0) & TXDESC_0_ES) == TXDESC_0_ES
     }
 
+    /// Error that the DMA engine has written back
+    ///
+    /// Checksum insertion errors also set the error summary, but the
+    /// frame is still transmitted, so they are not reported.
+    fn error(&self) -> Option<TxError> {
+        if !self.has_error() {
+            return None;
+        }
+        let tdes0 = self.desc.read(0);
+        [
+            (TXDESC_0_JT, TxError::JabberTimeout),
+            (TXDESC_0_FF, TxError::FrameFlushed),
+            (TXDESC_0_LCA, TxError::LossOfCarrier),
+            (TXDESC_0_NC, TxError::NoCarrier),
+            (TXDESC_0_LCO, TxError::LateCollision),
+            (TXDESC_0_EC, TxError::ExcessiveCollision),
+            (TXDESC_0_ED, TxError::ExcessiveDeferral),
+            (TXDESC_0_UF, TxError::UnderflowError),
+        ]
+        .iter()
+        .find(|(bit, _)| (tdes0 & bit) == *bit)
+        .map(|(_, error)| *error)
+    }
+
     fn set_buffer1(&mut self, buffer: *const u8) {
         unsafe {
             self.desc.write(2, buffer as u32);
@@ -90,11 +164,75 @@ impl TxDescriptor {
             self.desc.modify(1, |w| {
                 (w & !TXDESC_1_TBS_MASK) | ((len as u32) << TXDESC_1_TBS_SHIFT)
             });
-            self.desc.modify(0, |w| w | TXDESC_0_CIC1 | TXDESC_0_CIC2);
-            while (self.desc.read(0) & (TXDESC_0_CIC1 | TXDESC_0_CIC2))
-                != (TXDESC_0_CIC1 | TXDESC_0_CIC2)
-            {}
-            self.desc.modify(0, |w| w | TXDESC_0_CIC1 | TXDESC_0_CIC2);
+        }
+    }
+
+    /// Mark the descriptor as the first and/or last one of a frame
+    fn set_segment(&mut self, first: bool, last: bool) {
+        let mut flags = 0;
+        if first {
+            flags |= TXDESC_0_FS;
+        }
+        if last {
+            flags |= TXDESC_0_LS;
+        }
+        unsafe {
+            self.desc
+                .modify(0, |w| (w & !(TXDESC_0_FS | TXDESC_0_LS)) | flags);
+        }
+    }
+
+    /// Select which checksums the MAC inserts into the frame
+    fn set_checksum_insertion(&mut self, checksum: TxChecksum) {
+        let cic = checksum.bits() << TXDESC_0_CIC_SHIFT;
+        unsafe {
+            self.desc.modify(0, |w| (w & !TXDESC_0_CIC_MASK) | cic);
+        }
+    }
+
+    /// Let the MAC pad frames shorter than 60 bytes, or send them as
+    /// they are
+    fn set_padding(&mut self, enabled: bool) {
+        unsafe {
+            if enabled {
+                self.desc.modify(0, |w| w & !TXDESC_0_DP);
+            } else {
+                self.desc.modify(0, |w| w | TXDESC_0_DP);
+            }
+        }
+    }
+
+    /// Ask the MAC to capture the time of transmission
+    fn set_timestamp_enable(&mut self, enabled: bool) {
+        unsafe {
+            if enabled {
+                self.desc.modify(0, |w| w | TXDESC_0_TTSE);
+            } else {
+                self.desc.modify(0, |w| w & !TXDESC_0_TTSE);
+            }
+        }
+    }
+
+    /// Take the timestamp that the DMA engine has written back, and
+    /// restore the `next` descriptor address that it overwrote
+    fn take_timestamp(&mut self, next: u32) -> Option<u64> {
+        if (self.desc.read(0) & TXDESC_0_TTSS) != TXDESC_0_TTSS {
+            return None;
+        }
+        let low = self.desc.read(2);
+        let high = self.desc.read(3);
+        unsafe {
+            self.desc.modify(0, |w| w & !TXDESC_0_TTSS);
+            self.desc.write(3, next);
+        }
+        Some((u64::from(high) << 32) | u64::from(low))
+    }
+
+    /// Restore the `next` descriptor address, in case a timestamp
+    /// has not been taken
+    fn restore_buffer2(&mut self, next: u32) {
+        unsafe {
+            self.desc.write(3, next);
         }
     }
 
@@ -105,9 +243,13 @@ impl TxDescriptor {
         }
     }
 
-    fn set_end_of_ring(&mut self) {
+    fn set_end_of_ring(&mut self, end_of_ring: bool) {
         unsafe {
-            self.desc.modify(0, |w| w | TXDESC_0_TER);
+            if end_of_ring {
+                self.desc.modify(0, |w| w | TXDESC_0_TER);
+            } else {
+                self.desc.modify(0, |w| w & !TXDESC_0_TER);
+            }
         }
     }
 }
@@ -115,24 +257,44 @@ impl TxDescriptor {
 pub type TxRingEntry = RingEntry<TxDescriptor>;
 
 impl RingDescriptor for TxDescriptor {
-    fn setup(&mut self, buffer: *const u8, _len: usize, next: Option<&Self>) {
+    fn setup(&mut self, buffer: *const u8, _len: usize, next: *const Self, end_of_ring: bool) {
         self.set_buffer1(buffer);
-        match next {
-            Some(next) => self.set_buffer2(&next.desc as *const Descriptor as *const u8),
-            None => {
-                self.set_buffer2(0 as *const u8);
-                self.set_end_of_ring();
-            }
-        };
+        self.set_buffer2(next as *const u8);
+        self.set_end_of_ring(end_of_ring);
+    }
+
+    fn next_descriptor(&self) -> Option<u32> {
+        if (self.desc.read(0) & TXDESC_0_TCH) == TXDESC_0_TCH {
+            Some(self.desc.read(3))
+        } else {
+            None
+        }
+    }
+
+    fn is_end_of_ring(&self) -> bool {
+        (self.desc.read(0) & TXDESC_0_TER) == TXDESC_0_TER
     }
 }
 
 impl TxRingEntry {
-    fn prepare_packet<'a>(&'a mut self, length: usize) -> Option<TxPacket<'a>> {
-        assert!(length <= self.as_slice().len());
-
+    fn prepare_packet<'a>(
+        &'a mut self,
+        length: usize,
+        checksum_insertion: TxChecksum,
+        timestamp: bool,
+        padding: bool,
+    ) -> Option<TxPacket<'a>> {
         if !self.desc().is_owned() {
+            // May still point to a buffer from `send_buffer_ptr()`
+            let buffer = self.as_slice().as_ptr();
+            let next = self.next();
+            self.desc_mut().set_buffer1(buffer);
+            self.desc_mut().restore_buffer2(next);
             self.desc_mut().set_buffer1_len(length);
+            self.desc_mut().set_segment(true, true);
+            self.desc_mut().set_checksum_insertion(checksum_insertion);
+            self.desc_mut().set_timestamp_enable(timestamp);
+            self.desc_mut().set_padding(padding);
             Some(TxPacket {
                 entry: self,
                 length,
@@ -169,10 +331,104 @@ impl<'a> TxPacket<'a> {
     }
 }
 
+/// A free Tx entry whose buffer is filled in place, obtained with
+/// [`Eth::acquire_tx()`](../struct.Eth.html#method.acquire_tx)
+///
+/// Dereferences to the whole buffer of the entry. Nothing is sent
+/// if the slot is dropped without calling [`send()`](#method.send).
+pub struct TxSlot<'a, 'tx> {
+    ring: &'a mut TxRing<'tx>,
+    eth_dma: &'a ETHERNET_DMA,
+}
+
+impl<'a, 'tx> Deref for TxSlot<'a, 'tx> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.ring.entries[self.ring.next_entry].as_slice()
+    }
+}
+
+impl<'a, 'tx> DerefMut for TxSlot<'a, 'tx> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.ring.entries[self.ring.next_entry].as_mut_slice()
+    }
+}
+
+impl<'a, 'tx> TxSlot<'a, 'tx> {
+    /// Hand the first `length` bytes of the buffer to the DMA engine
+    pub fn send(self, length: usize) -> Result<(), TxError> {
+        if length > self.len() {
+            return Err(TxError::FrameTooLarge);
+        }
+        let result = self.ring.send(length, |_| ());
+        self.ring.demand_poll(self.eth_dma);
+        result
+    }
+}
+
+/// Iterator over the Tx entries that the DMA engine has finished,
+/// returned by
+/// [`Eth::drain_tx_completions()`](../struct.Eth.html#method.drain_tx_completions)
+///
+/// Yields the index of each entry along with its transmit status,
+/// in the order the frames were sent.
+pub struct TxCompletions<'a, 'tx> {
+    ring: &'a mut TxRing<'tx>,
+}
+
+impl<'a, 'tx> Iterator for TxCompletions<'a, 'tx> {
+    type Item = (usize, Result<(), TxError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.ring.take_completed()
+    }
+}
+
+/// Which checksums the MAC inserts into a transmitted frame, see
+/// [`Eth::send_with_checksum()`](../struct.Eth.html#method.send_with_checksum)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxChecksum {
+    /// Send the frame as it is, e.g. if it is already checksummed
+    /// or not IP at all
+    None,
+    /// Only the IPv4 header checksum. This keeps a deliberately
+    /// zero UDP checksum intact.
+    IpHeader,
+    /// The IPv4 header and the TCP/UDP/ICMP checksum. Software must
+    /// have stored the pseudo-header checksum in the checksum
+    /// field.
+    IpHeaderAndPayload,
+    /// The IPv4 header and the TCP/UDP/ICMP checksum, including the
+    /// pseudo-header
+    Full,
+}
+
+impl TxChecksum {
+    /// Value of the CIC field in TDES0
+    fn bits(self) -> u32 {
+        match self {
+            TxChecksum::None => 0b00,
+            TxChecksum::IpHeader => 0b01,
+            TxChecksum::IpHeaderAndPayload => 0b10,
+            TxChecksum::Full => 0b11,
+        }
+    }
+}
+
 /// Tx DMA state
 pub struct TxRing<'a> {
     entries: &'a mut [TxRingEntry],
     next_entry: usize,
+    /// Oldest entry that was handed to the DMA engine and has not
+    /// been reported as completed yet
+    completed_entry: usize,
+    /// Number of entries that have not been reported as completed,
+    /// at most the length of the ring
+    in_flight: usize,
+    checksum_insertion: TxChecksum,
+    timestamps: bool,
+    padding: bool,
 }
 
 impl<'a> TxRing<'a> {
@@ -183,20 +439,69 @@ impl<'a> TxRing<'a> {
         TxRing {
             entries,
             next_entry: 0,
+            completed_entry: 0,
+            in_flight: 0,
+            checksum_insertion: TxChecksum::Full,
+            timestamps: false,
+            padding: true,
         }
     }
 
+    /// Whether `send()` asks the MAC to insert checksums
+    pub fn set_checksum_insertion(&mut self, insert: bool) {
+        self.checksum_insertion = if insert {
+            TxChecksum::Full
+        } else {
+            TxChecksum::None
+        };
+    }
+
+    /// Capture the time of transmission of the frames sent from now
+    /// on
+    pub fn set_timestamps(&mut self, enabled: bool) {
+        self.timestamps = enabled;
+    }
+
+    /// Let the MAC pad short frames to the minimum length
+    pub fn set_padding(&mut self, enabled: bool) {
+        self.padding = enabled;
+    }
+
+    /// Take the timestamp of the oldest transmitted frame whose
+    /// timestamp has not been taken yet
+    pub fn take_timestamp(&mut self) -> Option<u64> {
+        let entries_len = self.entries.len();
+        // Starting after the most recently used entry, so that
+        // timestamps are returned in the order they were captured
+        (0..entries_len)
+            .map(|i| (self.next_entry + i) % entries_len)
+            .find_map(|index| {
+                let entry = &mut self.entries[index];
+                let next = entry.next();
+                let desc = entry.desc_mut();
+                if desc.is_owned() {
+                    None
+                } else {
+                    // The timestamp is written before OWN is cleared
+                    fence(Ordering::Acquire);
+                    desc.take_timestamp(next)
+                }
+            })
+    }
+
     /// Start the Tx DMA engine
+    ///
+    /// Packets that were still queued when the engine was stopped
+    /// are discarded.
     pub fn start(&mut self, eth_dma: &ETHERNET_DMA) {
         // Setup ring
-        {
-            let mut previous: Option<&mut TxRingEntry> = None;
-            for entry in self.entries.iter_mut() {
-                previous.map(|previous| previous.setup(Some(entry)));
-                previous = Some(entry);
-            }
-            previous.map(|previous| previous.setup(None));
+        for entry in self.entries.iter_mut() {
+            entry.desc_mut().clear_owned();
         }
+        setup_chain(self.entries);
+        self.next_entry = 0;
+        self.completed_entry = 0;
+        self.in_flight = 0;
 
         let ring_ptr = self.entries[0].desc() as *const TxDescriptor;
         // Register TxDescriptor
@@ -210,13 +515,33 @@ impl<'a> TxRing<'a> {
         &mut self,
         length: usize,
         f: F,
+    ) -> Result<R, TxError> {
+        self.send_with_checksum(length, self.checksum_insertion, f)
+    }
+
+    pub fn send_with_checksum<F: FnOnce(&mut [u8]) -> R, R>(
+        &mut self,
+        length: usize,
+        checksum_insertion: TxChecksum,
+        f: F,
     ) -> Result<R, TxError> {
         let entries_len = self.entries.len();
+        if length > self.entries[self.next_entry].as_slice().len() {
+            return Err(TxError::FrameTooLarge);
+        }
 
-        match self.entries[self.next_entry].prepare_packet(length) {
+        let timestamps = self.timestamps;
+        let padding = self.padding;
+        match self.entries[self.next_entry].prepare_packet(
+            length,
+            checksum_insertion,
+            timestamps,
+            padding,
+        ) {
             Some(mut pkt) => {
                 let r = f(pkt.deref_mut());
                 pkt.send();
+                self.mark_queued(1);
 
                 self.next_entry += 1;
                 if self.next_entry >= entries_len {
@@ -228,9 +553,203 @@ impl<'a> TxRing<'a> {
         }
     }
 
+    /// Copy each non-empty segment into its own descriptor, so that
+    /// the DMA engine transmits them as one frame
+    pub fn send_segments(&mut self, segments: &[&[u8]]) -> Result<(), TxError> {
+        let entries_len = self.entries.len();
+        let count = segments.iter().filter(|segment| !segment.is_empty()).count();
+        let buffer_len = self.entries[0].as_slice().len();
+        if count > entries_len || segments.iter().any(|segment| segment.len() > buffer_len) {
+            return Err(TxError::FrameTooLarge);
+        }
+        let all_available = (0..count).all(|i| {
+            !self.entries[(self.next_entry + i) % entries_len]
+                .desc()
+                .is_owned()
+        });
+        if !all_available {
+            return Err(TxError::WouldBlock);
+        }
+
+        let first = self.next_entry;
+        let checksum_insertion = self.checksum_insertion;
+        let timestamps = self.timestamps;
+        let padding = self.padding;
+        let segments = segments.iter().filter(|segment| !segment.is_empty());
+        for (i, segment) in segments.enumerate() {
+            let index = (first + i) % entries_len;
+            let entry = &mut self.entries[index];
+            entry.as_mut_slice()[..segment.len()].copy_from_slice(segment);
+            let buffer = entry.as_slice().as_ptr();
+            let next = entry.next();
+            let desc = entry.desc_mut();
+            desc.set_buffer1(buffer);
+            desc.restore_buffer2(next);
+            desc.set_buffer1_len(segment.len());
+            desc.set_segment(i == 0, i + 1 == count);
+            desc.set_checksum_insertion(checksum_insertion);
+            // The timestamp is captured with the last segment
+            desc.set_timestamp_enable(timestamps && i + 1 == count);
+            desc.set_padding(padding);
+            // The first descriptor is handed over last, so that the
+            // DMA engine does not start on an incomplete frame.
+            if i != 0 {
+                desc.set_owned();
+            }
+        }
+        if count > 0 {
+            self.entries[first].desc_mut().set_owned();
+        }
+
+        self.next_entry = (first + count) % entries_len;
+        self.mark_queued(count);
+        Ok(())
+    }
+
+    /// Iterate over the entries completed since the last poll
+    pub fn completions(&mut self) -> TxCompletions<'_, 'a> {
+        TxCompletions { ring: self }
+    }
+
+    /// The next entry, if the DMA engine has released it
+    pub fn acquire<'s>(&'s mut self, eth_dma: &'s ETHERNET_DMA) -> Option<TxSlot<'s, 'a>> {
+        if self.entries[self.next_entry].desc().is_owned() {
+            None
+        } else {
+            Some(TxSlot {
+                ring: self,
+                eth_dma,
+            })
+        }
+    }
+
+    /// Send `len` bytes at `ptr` without copying them into the ring
+    ///
+    /// # Safety
+    ///
+    /// See [`Eth::send_buffer_ptr()`](../struct.Eth.html#method.send_buffer_ptr).
+    pub unsafe fn send_buffer_ptr(&mut self, ptr: *const u8, len: usize) -> Result<(), TxError> {
+        let checksum_insertion = self.checksum_insertion;
+        let timestamps = self.timestamps;
+        let padding = self.padding;
+        let entry = &mut self.entries[self.next_entry];
+        if entry.desc().is_owned() {
+            return Err(TxError::WouldBlock);
+        }
+        let next = entry.next();
+        let desc = entry.desc_mut();
+        desc.set_buffer1(ptr);
+        desc.restore_buffer2(next);
+        desc.set_buffer1_len(len);
+        desc.set_segment(true, true);
+        desc.set_checksum_insertion(checksum_insertion);
+        desc.set_timestamp_enable(timestamps);
+        desc.set_padding(padding);
+        desc.set_owned();
+        self.mark_queued(1);
+
+        self.next_entry = (self.next_entry + 1) % self.entries.len();
+        Ok(())
+    }
+
+    /// Account for `count` entries that have been handed to the DMA
+    /// engine at `next_entry` and onwards
+    ///
+    /// The oldest unreported entry is the one reused once all of
+    /// them are unreported. Its completion is dropped, so that it is
+    /// not attributed to the frame it now holds.
+    fn mark_queued(&mut self, count: usize) {
+        let entries_len = self.entries.len();
+        for _ in 0..count {
+            if self.in_flight == entries_len {
+                self.completed_entry = (self.completed_entry + 1) % entries_len;
+            } else {
+                self.in_flight += 1;
+            }
+        }
+    }
+
+    /// Count the entries that the DMA engine has finished since the
+    /// last call, in the order they were sent
+    ///
+    /// Stops before an entry that reports an error, which is then
+    /// returned by the next call, so that no completion is lost.
+    pub fn poll_completed(&mut self) -> Result<usize, TxError> {
+        let mut completed = 0;
+        while self.in_flight > 0 && !self.entries[self.completed_entry].desc().is_owned() {
+            // The status is written before OWN is cleared
+            fence(Ordering::Acquire);
+            let error = self.entries[self.completed_entry].desc().error();
+            if error.is_some() && completed > 0 {
+                break;
+            }
+            self.completed_entry = (self.completed_entry + 1) % self.entries.len();
+            self.in_flight -= 1;
+            completed += 1;
+            if let Some(error) = error {
+                return Err(error);
+            }
+        }
+        Ok(completed)
+    }
+
+    /// Index and status of the oldest entry that the DMA engine
+    /// has finished and that has not been reported yet
+    pub fn take_completed(&mut self) -> Option<(usize, Result<(), TxError>)> {
+        let index = self.completed_entry;
+        if self.in_flight == 0 || self.entries[index].desc().is_owned() {
+            return None;
+        }
+        // The status is written before OWN is cleared
+        fence(Ordering::Acquire);
+        let status = match self.entries[index].desc().error() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        };
+        self.completed_entry = (index + 1) % self.entries.len();
+        self.in_flight -= 1;
+        Some((index, status))
+    }
+
+    /// Number of entries that are not owned by the DMA engine
+    pub fn available(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| !entry.desc().is_owned())
+            .count()
+    }
+
+    /// Raw words of the descriptor at `index`
+    #[cfg(any(test, feature = "debug-descriptors"))]
+    pub fn descriptor_words(&self, index: usize) -> [u32; 4] {
+        self.entries[index].desc().desc.words()
+    }
+
+    /// Index of the entry that the DMA engine is currently working
+    /// on
+    pub fn current_entry(&self, eth_dma: &ETHERNET_DMA) -> Option<usize> {
+        let addr = eth_dma.dmachtdr.read().bits() as usize;
+        self.entries
+            .iter()
+            .position(|entry| entry.desc() as *const TxDescriptor as usize == addr)
+    }
+
+    /// Can the next entry be used for sending?
+    pub fn next_entry_available(&self) -> bool {
+        !self.entries[self.next_entry].desc().is_owned()
+    }
+
+    /// Stop the Tx DMA engine once the current frame is transmitted
+    pub fn stop(&self, eth_dma: &ETHERNET_DMA) {
+        eth_dma.dmaomr.modify(|_, w| w.st().clear_bit());
+    }
+
     /// Demand that the DMA engine polls the current `TxDescriptor`
     /// (when we just transferred ownership to the hardware).
     pub fn demand_poll(&self, eth_dma: &ETHERNET_DMA) {
+        // Make sure the OWN bit is visible before the DMA engine
+        // starts polling
+        fence(Ordering::SeqCst);
         eth_dma.dmatpdr.write(|w| w.tpd().poll());
     }
 
@@ -239,7 +758,8 @@ impl<'a> TxRing<'a> {
         self.running_state(&eth_dma).is_running()
     }
 
-    fn running_state(&self, eth_dma: &ETHERNET_DMA) -> RunningState {
+    /// Get current `RunningState`
+    pub fn running_state(&self, eth_dma: &ETHERNET_DMA) -> RunningState {
         match eth_dma.dmasr.read().tps().bits() {
             // Reset or Stop Transmit Command issued
             0b000 => RunningState::Stopped,
@@ -249,32 +769,11 @@ impl<'a> TxRing<'a> {
             0b010 => RunningState::Running,
             // Reading Data from host memory buffer and queuing it to transmit buffer
             0b011 => RunningState::Running,
-            0b100 | 0b101 => RunningState::Reserved,
             // Transmit descriptor unavailable
             0b110 => RunningState::Suspended,
+            // Closing transmit descriptor
+            0b111 => RunningState::Running,
             _ => RunningState::Unknown,
         }
     }
 }
-
-#[derive(Debug, PartialEq)]
-enum RunningState {
-    /// Reset or Stop Transmit Command issued
-    Stopped,
-    /// Fetching transmit transfer descriptor;
-    /// Waiting for status;
-    /// Reading Data from host memory buffer and queuing it to transmit buffer
-    Running,
-    /// Reserved for future use
-    Reserved,
-    /// Transmit descriptor unavailable
-    Suspended,
-    /// Invalid value
-    Unknown,
-}
-
-impl RunningState {
-    pub fn is_running(&self) -> bool {
-        *self == RunningState::Running
-    }
-}
@@ -0,0 +1,60 @@
+//! IEEE 802.1Q VLAN tagging
+
+use crate::{Eth, SaMode, TxError, MAX_FRAME_LEN};
+
+/// EtherType of the tag protocol identifier
+const ETHERTYPE_VLAN: u16 = 0x8100;
+/// Length of a VLAN tag
+const VLAN_TAG_LEN: usize = 4;
+/// Length of destination and source address, after which the tag
+/// is inserted
+const ADDRESSES_LEN: usize = 12;
+
+impl<'rx, 'tx> Eth<'rx, 'tx> {
+    /// Send the untagged frame `data` with an 802.1Q tag inserted
+    /// after the source address
+    ///
+    /// The MACs of the supported chips cannot insert VLAN tags
+    /// themselves, so the tag is inserted while `data` is copied
+    /// into the Tx buffer. Frames sent otherwise are not affected.
+    ///
+    /// As `data` always carries a source address, it is only
+    /// overwritten in [`SaMode::Replace`](enum.SaMode.html), and
+    /// `SaMode::Insert` does not apply to these frames.
+    ///
+    /// Fails with `TxError::InvalidVlanTag` if `vlan_id` does not
+    /// fit into 12 bits or `priority` does not fit into 3 bits, with
+    /// `TxError::FrameTooShort` if `data` is shorter than the two
+    /// addresses, and with `TxError::FrameTooLarge` if the tagged
+    /// frame does not fit into a Tx buffer.
+    pub fn send_vlan(&mut self, vlan_id: u16, priority: u8, data: &[u8]) -> Result<(), TxError> {
+        if vlan_id > 0xFFF || priority > 7 {
+            return Err(TxError::InvalidVlanTag);
+        }
+        if data.len() < ADDRESSES_LEN {
+            return Err(TxError::FrameTooShort);
+        }
+
+        let length = data.len() + VLAN_TAG_LEN;
+        if length > MAX_FRAME_LEN + VLAN_TAG_LEN {
+            return Err(TxError::FrameTooLarge);
+        }
+        let tci = (u16::from(priority) << 13) | vlan_id;
+        let source = match self.source_address_mode {
+            SaMode::Replace => Some(self.mac_address()),
+            _ => None,
+        };
+        let result = self.tx_ring.send(length, |buf| {
+            let (addresses, payload) = data.split_at(ADDRESSES_LEN);
+            buf[..ADDRESSES_LEN].copy_from_slice(addresses);
+            buf[ADDRESSES_LEN..ADDRESSES_LEN + 2].copy_from_slice(&ETHERTYPE_VLAN.to_be_bytes());
+            buf[ADDRESSES_LEN + 2..ADDRESSES_LEN + 4].copy_from_slice(&tci.to_be_bytes());
+            buf[ADDRESSES_LEN + VLAN_TAG_LEN..].copy_from_slice(payload);
+            if let Some(mac) = source {
+                buf[ADDRESSES_LEN / 2..ADDRESSES_LEN].copy_from_slice(mac.as_bytes());
+            }
+        });
+        self.tx_ring.demand_poll(&self.eth_dma);
+        result
+    }
+}